@@ -0,0 +1,67 @@
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use std::hint::black_box;
+use video_14_NN::{Activation, Matrix, Network};
+
+/// The original triple loop, kept here as the baseline.
+fn naive_dot(a: &Matrix, b: &Matrix) -> Matrix {
+    let mut data = vec![0.0; a.rows * b.cols];
+    for i in 0..a.rows {
+        for j in 0..b.cols {
+            let mut sum = 0.0;
+            for k in 0..a.cols {
+                sum += a.data[i * a.cols + k] * b.data[k * b.cols + j];
+            }
+            data[i * b.cols + j] = sum;
+        }
+    }
+    Matrix {
+        rows: a.rows,
+        cols: b.cols,
+        data,
+    }
+}
+
+fn square(n: usize) -> Matrix {
+    Matrix {
+        rows: n,
+        cols: n,
+        data: (0..n * n).map(|i| (i % 17) as f64 / 17.0).collect(),
+    }
+}
+
+fn matmul(c: &mut Criterion) {
+    let mut group = c.benchmark_group("matmul");
+
+    for n in [32, 128, 256, 512] {
+        let (a, b) = (square(n), square(n));
+        let mut out = Matrix::default();
+
+        group.bench_with_input(BenchmarkId::new("naive", n), &n, |bench, _| {
+            bench.iter(|| naive_dot(black_box(&a), black_box(&b)))
+        });
+        group.bench_with_input(BenchmarkId::new("blocked", n), &n, |bench, _| {
+            bench.iter(|| black_box(&a).dot_multiply(black_box(&b)))
+        });
+        group.bench_with_input(BenchmarkId::new("blocked_into", n), &n, |bench, _| {
+            bench.iter(|| black_box(&a).dot_multiply_into(black_box(&b), &mut out))
+        });
+    }
+
+    group.finish();
+}
+
+fn feed_forward(c: &mut Criterion) {
+    let mut network = Network::new(vec![784, 128, 64, 10], Activation::Relu, 0.1, Some(0));
+    let batch = Matrix {
+        rows: 784,
+        cols: 64,
+        data: vec![0.5; 784 * 64],
+    };
+
+    c.bench_function("feed_forward_784_128_64_10_batch64", |bench| {
+        bench.iter(|| network.feed_forward(black_box(batch.clone())))
+    });
+}
+
+criterion_group!(benches, matmul, feed_forward);
+criterion_main!(benches);
@@ -0,0 +1,130 @@
+//! Trains a small classifier and reports test accuracy and a confusion matrix.
+//!
+//! The per-epoch validation metrics come from a split of the training data, so the
+//! test set is only seen once at the end.
+//!
+//! ```text
+//! cargo run --release --example classify                # IDX fixtures
+//! cargo run --release --example classify -- idx TRAIN_IMAGES TRAIN_LABELS TEST_IMAGES TEST_LABELS CLASSES
+//! cargo run --release --example classify -- csv FILE
+//! ```
+
+use std::{env, error::Error, path::Path};
+
+use video_14_NN::*;
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures");
+
+    let (train, test) =
+        match args.first().map(String::as_str) {
+            None => load_idx_split(
+                &fixtures.join("shapes-train-images.idx3-ubyte"),
+                &fixtures.join("shapes-train-labels.idx1-ubyte"),
+                &fixtures.join("shapes-test-images.idx3-ubyte"),
+                &fixtures.join("shapes-test-labels.idx1-ubyte"),
+                3,
+            )?,
+            Some("idx") if args.len() == 6 => load_idx_split(
+                Path::new(&args[1]),
+                Path::new(&args[2]),
+                Path::new(&args[3]),
+                Path::new(&args[4]),
+                args[5].parse()?,
+            )?,
+            Some("csv") if args.len() <= 2 => {
+                let path = args.get(1).map_or(fixtures.join("blobs.csv"), Into::into);
+                let dataset = load_csv(path, &CsvOptions::default())?;
+                let (train, test) = dataset.split(0.3, 1);
+
+                // Statistics come from the training split only.
+                let normalizer = Normalizer::fit(&train.inputs);
+                (
+                    Dataset::new(normalizer.apply(&train.inputs), train.targets),
+                    Dataset::new(normalizer.apply(&test.inputs), test.targets),
+                )
+            }
+            _ => return Err(
+                "usage: classify [idx IMAGES LABELS TEST_IMAGES TEST_LABELS CLASSES | csv [FILE]]"
+                    .into(),
+            ),
+        };
+
+    let (train, validation) = train.split(0.2, 7);
+
+    let (inputs, classes) = (train.inputs.rows, train.targets.rows);
+    println!(
+        "{} training, {} validation and {} test samples, {inputs} features, {classes} classes",
+        train.len(),
+        validation.len(),
+        test.len()
+    );
+
+    let mut network = Network::builder(inputs)
+        .layer(32, Activation::Relu)
+        .batch_norm()
+        .dropout(0.1)
+        .layer(16, Activation::Relu)
+        .layer(classes, Activation::Softmax)
+        .loss(Loss::CategoricalCrossEntropy)
+        .optimizer(Adam::new())
+        .learning_rate(0.01)
+        .seed(7)
+        .build();
+    let config = TrainConfig {
+        epochs: 60,
+        batch_size: 16,
+        seed: Some(7),
+        ..Default::default()
+    };
+
+    network.fit(&train, Some(&validation), &config, |metrics| {
+        if metrics.epoch % 10 == 0 {
+            println!(
+                "Epoch {:>3}: loss {:.4}, accuracy {:.3}, validation loss {:.4}",
+                metrics.epoch,
+                metrics.loss,
+                metrics.accuracy,
+                metrics.val_loss.unwrap_or(f64::NAN),
+            );
+        }
+    });
+
+    network.set_mode(Mode::Eval);
+    let outputs = network.feed_forward(test.inputs.clone());
+    println!(
+        "\nTest accuracy: {:.1}%",
+        accuracy(&outputs, &test.targets) * 100.0
+    );
+
+    println!("\nConfusion matrix (rows: actual, columns: predicted)");
+    let counts = confusion_matrix(&outputs, &test.targets);
+    print!("     ");
+    for predicted in 0..classes {
+        print!("{predicted:>5}");
+    }
+    println!();
+    for (actual, row) in counts.iter().enumerate() {
+        print!("{actual:>5}");
+        for count in row {
+            print!("{count:>5}");
+        }
+        println!();
+    }
+
+    Ok(())
+}
+
+fn load_idx_split(
+    train_images: &Path,
+    train_labels: &Path,
+    test_images: &Path,
+    test_labels: &Path,
+    classes: usize,
+) -> Result<(Dataset, Dataset), DataError> {
+    Ok((
+        load_idx(train_images, train_labels, classes)?,
+        load_idx(test_images, test_labels, classes)?,
+    ))
+}
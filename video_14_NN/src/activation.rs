@@ -0,0 +1,214 @@
+use crate::Matrix;
+
+/// Activation applied to the output of a dense layer.
+///
+/// Derivatives are expressed in terms of the activation *output*, which is what
+/// `Network` keeps around after `feed_forward`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Activation {
+    Identity,
+    Sigmoid,
+    Tanh,
+    Relu,
+    LeakyRelu(f64),
+    /// Normalizes every column (sample) into a probability distribution.
+    Softmax,
+}
+
+impl Activation {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Activation::Identity => "identity",
+            Activation::Sigmoid => "sigmoid",
+            Activation::Tanh => "tanh",
+            Activation::Relu => "relu",
+            Activation::LeakyRelu(_) => "leaky_relu",
+            Activation::Softmax => "softmax",
+        }
+    }
+
+    /// Inverse of [`Activation::name`]; `alpha` is only used by leaky ReLU.
+    pub fn from_name(name: &str, alpha: Option<f64>) -> Option<Activation> {
+        let activation = match name {
+            "identity" => Activation::Identity,
+            "sigmoid" => Activation::Sigmoid,
+            "tanh" => Activation::Tanh,
+            "relu" => Activation::Relu,
+            "leaky_relu" => Activation::LeakyRelu(alpha?),
+            "softmax" => Activation::Softmax,
+            _ => return None,
+        };
+        Some(activation)
+    }
+
+    pub fn forward(&self, inputs: &Matrix) -> Matrix {
+        let mut outputs = inputs.clone();
+        self.forward_in_place(&mut outputs);
+        outputs
+    }
+
+    /// [`Activation::forward`] overwriting `values` instead of allocating.
+    pub fn forward_in_place(&self, values: &mut Matrix) {
+        match self {
+            Activation::Softmax => softmax_in_place(values),
+            _ => values.map_in_place(|x| self.function(x)),
+        }
+    }
+
+    /// Turns the gradient w.r.t. the outputs into the gradient w.r.t. the inputs.
+    pub fn backward(&self, outputs: &Matrix, output_gradients: &Matrix) -> Matrix {
+        match self {
+            Activation::Softmax => softmax_backward(outputs, output_gradients),
+            _ => {
+                let derivatives = outputs.map(|y| self.derivative(y));
+                derivatives.elementwise_multiply(output_gradients)
+            }
+        }
+    }
+
+    fn function(&self, x: f64) -> f64 {
+        match self {
+            Activation::Identity => x,
+            Activation::Sigmoid => 1.0 / (1.0 + (-x).exp()),
+            Activation::Tanh => x.tanh(),
+            Activation::Relu => x.max(0.0),
+            Activation::LeakyRelu(alpha) => {
+                if x > 0.0 {
+                    x
+                } else {
+                    alpha * x
+                }
+            }
+            Activation::Softmax => unreachable!("softmax is not elementwise"),
+        }
+    }
+
+    fn derivative(&self, y: f64) -> f64 {
+        match self {
+            Activation::Identity => 1.0,
+            Activation::Sigmoid => y * (1.0 - y),
+            Activation::Tanh => 1.0 - y * y,
+            Activation::Relu => {
+                if y > 0.0 {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            Activation::LeakyRelu(alpha) => {
+                if y > 0.0 {
+                    1.0
+                } else {
+                    *alpha
+                }
+            }
+            Activation::Softmax => unreachable!("softmax is not elementwise"),
+        }
+    }
+}
+
+fn softmax_in_place(values: &mut Matrix) {
+    let cols = values.cols;
+
+    for j in 0..cols {
+        let column = values.data.iter().skip(j).step_by(cols);
+        let max = column.clone().copied().fold(f64::NEG_INFINITY, f64::max);
+        let sum: f64 = column.map(|x| (x - max).exp()).sum();
+
+        for x in values.data.iter_mut().skip(j).step_by(cols) {
+            *x = (*x - max).exp() / sum;
+        }
+    }
+}
+
+/// Jacobian-vector product of softmax, per column: `y * (g - dot(y, g))`.
+fn softmax_backward(outputs: &Matrix, output_gradients: &Matrix) -> Matrix {
+    let mut data = vec![0.0; outputs.data.len()];
+
+    for j in 0..outputs.cols {
+        let dot: f64 = (0..outputs.rows)
+            .map(|i| {
+                outputs.data[i * outputs.cols + j] * output_gradients.data[i * outputs.cols + j]
+            })
+            .sum();
+
+        for i in 0..outputs.rows {
+            let idx = i * outputs.cols + j;
+            data[idx] = outputs.data[idx] * (output_gradients.data[idx] - dot);
+        }
+    }
+
+    Matrix {
+        rows: outputs.rows,
+        cols: outputs.cols,
+        data,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn softmax_columns_sum_to_one() {
+        let inputs = Matrix {
+            rows: 3,
+            cols: 2,
+            data: vec![1.0, -1.0, 2.0, 0.0, 3.0, 1000.0],
+        };
+
+        let outputs = Activation::Softmax.forward(&inputs);
+
+        for j in 0..2 {
+            let sum: f64 = (0..3).map(|i| outputs.data[i * 2 + j]).sum();
+            assert!((sum - 1.0).abs() < 1e-12);
+        }
+        assert!((outputs.data[5] - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn backward_matches_finite_differences() {
+        let activations = [
+            Activation::Identity,
+            Activation::Sigmoid,
+            Activation::Tanh,
+            Activation::Relu,
+            Activation::LeakyRelu(0.1),
+            Activation::Softmax,
+        ];
+        let inputs = Matrix::from(vec![0.3, -0.7, 1.2]);
+        let weights = Matrix::from(vec![0.5, -1.5, 2.0]);
+        let eps = 1e-6;
+
+        // Scalar objective: sum(weights * activation(inputs)).
+        let objective = |activation: &Activation, inputs: &Matrix| -> f64 {
+            let outputs = activation.forward(inputs);
+            outputs
+                .data
+                .iter()
+                .zip(&weights.data)
+                .map(|(y, w)| y * w)
+                .sum()
+        };
+
+        for activation in &activations {
+            let outputs = activation.forward(&inputs);
+            let analytic = activation.backward(&outputs, &weights);
+
+            for k in 0..inputs.data.len() {
+                let mut plus = inputs.clone();
+                plus.data[k] += eps;
+                let mut minus = inputs.clone();
+                minus.data[k] -= eps;
+                let numeric =
+                    (objective(activation, &plus) - objective(activation, &minus)) / (2.0 * eps);
+
+                assert!(
+                    (numeric - analytic.data[k]).abs() < 1e-6,
+                    "{activation:?} input {k}: numeric {numeric} analytic {}",
+                    analytic.data[k]
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,388 @@
+use std::{fmt, path::Path};
+
+use crate::{Dataset, Matrix};
+
+#[derive(Debug)]
+pub enum DataError {
+    Io(std::io::Error),
+    /// Malformed IDX data.
+    Idx(String),
+    /// Malformed CSV data; `line` is 1-based.
+    Csv {
+        line: usize,
+        message: String,
+    },
+    LabelOutOfRange {
+        label: usize,
+        classes: usize,
+    },
+    /// Images and labels disagree on the number of samples.
+    SampleCountMismatch {
+        inputs: usize,
+        labels: usize,
+    },
+}
+
+impl fmt::Display for DataError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DataError::Io(err) => write!(f, "io error: {err}"),
+            DataError::Idx(msg) => write!(f, "invalid idx data: {msg}"),
+            DataError::Csv { line, message } => write!(f, "csv line {line}: {message}"),
+            DataError::LabelOutOfRange { label, classes } => {
+                write!(f, "label {label} out of range for {classes} classes")
+            }
+            DataError::SampleCountMismatch { inputs, labels } => {
+                write!(f, "{inputs} samples but {labels} labels")
+            }
+        }
+    }
+}
+
+impl std::error::Error for DataError {}
+
+impl From<std::io::Error> for DataError {
+    fn from(err: std::io::Error) -> Self {
+        DataError::Io(err)
+    }
+}
+
+/// Contents of an IDX file (the MNIST format), restricted to unsigned byte data.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IdxArray {
+    pub dims: Vec<usize>,
+    pub data: Vec<u8>,
+}
+
+const IDX_UNSIGNED_BYTE: u8 = 0x08;
+
+pub fn parse_idx(bytes: &[u8]) -> Result<IdxArray, DataError> {
+    let [0, 0, data_type, dim_count, rest @ ..] = bytes else {
+        return Err(DataError::Idx("bad magic number".to_string()));
+    };
+    if *data_type != IDX_UNSIGNED_BYTE {
+        return Err(DataError::Idx(format!(
+            "unsupported data type {data_type:#04x}"
+        )));
+    }
+
+    let header_len = *dim_count as usize * 4;
+    if rest.len() < header_len {
+        return Err(DataError::Idx("truncated header".to_string()));
+    }
+    let (header, data) = rest.split_at(header_len);
+    let dims: Vec<usize> = header
+        .chunks_exact(4)
+        .map(|d| u32::from_be_bytes(d.try_into().unwrap()) as usize)
+        .collect();
+
+    let len = dims
+        .iter()
+        .try_fold(1usize, |len, &d| len.checked_mul(d))
+        .ok_or_else(|| DataError::Idx("dimensions overflow".to_string()))?;
+    if data.len() != len {
+        return Err(DataError::Idx(format!(
+            "dimensions {dims:?} need {len} bytes, found {}",
+            data.len()
+        )));
+    }
+
+    Ok(IdxArray {
+        dims,
+        data: data.to_vec(),
+    })
+}
+
+pub fn read_idx(path: impl AsRef<Path>) -> Result<IdxArray, DataError> {
+    parse_idx(&std::fs::read(path)?)
+}
+
+/// Images scaled from `0..=255` to `0.0..=1.0`, one flattened image per column, with
+/// one-hot targets.
+pub fn load_idx(
+    images: impl AsRef<Path>,
+    labels: impl AsRef<Path>,
+    classes: usize,
+) -> Result<Dataset, DataError> {
+    let images = read_idx(images)?;
+    let labels = read_idx(labels)?;
+
+    let Some((&count, pixel_dims)) = images.dims.split_first() else {
+        return Err(DataError::Idx("images have no dimensions".to_string()));
+    };
+    if labels.dims.len() != 1 {
+        return Err(DataError::Idx(format!(
+            "labels must be one-dimensional, found {:?}",
+            labels.dims
+        )));
+    }
+    if labels.dims[0] != count {
+        return Err(DataError::SampleCountMismatch {
+            inputs: count,
+            labels: labels.dims[0],
+        });
+    }
+
+    let pixels: usize = pixel_dims.iter().product();
+    let mut inputs = Matrix {
+        rows: pixels,
+        cols: count,
+        data: vec![0.0; pixels * count],
+    };
+    for (sample, image) in images.data.chunks_exact(pixels.max(1)).enumerate() {
+        for (pixel, &value) in image.iter().enumerate() {
+            inputs.data[pixel * count + sample] = value as f64 / 255.0;
+        }
+    }
+
+    let labels: Vec<usize> = labels.data.iter().map(|&l| l as usize).collect();
+    Ok(Dataset::new(inputs, one_hot(&labels, classes)?))
+}
+
+#[derive(Debug, Clone)]
+pub struct CsvOptions {
+    pub has_header: bool,
+    pub delimiter: char,
+    /// Column holding the integer class label, `None` for the last one.
+    pub label_column: Option<usize>,
+    /// Number of classes, `None` to use the largest label plus one.
+    pub classes: Option<usize>,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        CsvOptions {
+            has_header: true,
+            delimiter: ',',
+            label_column: None,
+            classes: None,
+        }
+    }
+}
+
+/// Every column but the label becomes a (raw, unnormalized) input feature.
+pub fn parse_csv(text: &str, options: &CsvOptions) -> Result<Dataset, DataError> {
+    let mut features: Vec<Vec<f64>> = vec![];
+    let mut labels = vec![];
+
+    let lines = text
+        .lines()
+        .enumerate()
+        .skip(options.has_header as usize)
+        .filter(|(_, line)| !line.trim().is_empty());
+    for (index, line) in lines {
+        let csv_error = |message: String| DataError::Csv {
+            line: index + 1,
+            message,
+        };
+
+        let fields: Vec<&str> = line.split(options.delimiter).map(str::trim).collect();
+        let label_column = options
+            .label_column
+            .unwrap_or(fields.len().saturating_sub(1));
+        if label_column >= fields.len() {
+            return Err(csv_error(format!("no label column {label_column}")));
+        }
+        if let Some(first) = features.first()
+            && first.len() != fields.len() - 1
+        {
+            return Err(csv_error(format!(
+                "expected {} fields, found {}",
+                first.len() + 1,
+                fields.len()
+            )));
+        }
+
+        let label = fields[label_column]
+            .parse::<usize>()
+            .map_err(|_| csv_error(format!("invalid label '{}'", fields[label_column])))?;
+        let row = fields
+            .iter()
+            .enumerate()
+            .filter(|&(j, _)| j != label_column)
+            .map(|(_, field)| {
+                field
+                    .parse::<f64>()
+                    .map_err(|_| csv_error(format!("invalid number '{field}'")))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        features.push(row);
+        labels.push(label);
+    }
+
+    let classes = options
+        .classes
+        .unwrap_or_else(|| labels.iter().max().map_or(0, |max| max + 1));
+    Ok(Dataset::new(
+        Matrix::from_columns(&features),
+        one_hot(&labels, classes)?,
+    ))
+}
+
+pub fn load_csv(path: impl AsRef<Path>, options: &CsvOptions) -> Result<Dataset, DataError> {
+    parse_csv(&std::fs::read_to_string(path)?, options)
+}
+
+/// `classes x labels.len()` matrix with a single 1.0 per column.
+pub fn one_hot(labels: &[usize], classes: usize) -> Result<Matrix, DataError> {
+    let mut targets = Matrix {
+        rows: classes,
+        cols: labels.len(),
+        data: vec![0.0; classes * labels.len()],
+    };
+    for (sample, &label) in labels.iter().enumerate() {
+        if label >= classes {
+            return Err(DataError::LabelOutOfRange { label, classes });
+        }
+        targets.data[label * labels.len() + sample] = 1.0;
+    }
+    Ok(targets)
+}
+
+/// Per-feature standardization to zero mean and unit variance.
+///
+/// Fit it on the training inputs only and apply the same instance to every split.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Normalizer {
+    pub mean: Vec<f64>,
+    pub std_dev: Vec<f64>,
+}
+
+impl Normalizer {
+    pub fn fit(inputs: &Matrix) -> Self {
+        let n = inputs.cols.max(1) as f64;
+        let rows = || inputs.data.chunks(inputs.cols.max(1));
+
+        let mean: Vec<f64> = rows().map(|row| row.iter().sum::<f64>() / n).collect();
+        let std_dev = rows()
+            .zip(&mean)
+            .map(|(row, m)| {
+                let variance = row.iter().map(|x| (x - m).powi(2)).sum::<f64>() / n;
+                // Constant features are only centered.
+                if variance > 0.0 { variance.sqrt() } else { 1.0 }
+            })
+            .collect();
+
+        Normalizer { mean, std_dev }
+    }
+
+    pub fn apply(&self, inputs: &Matrix) -> Matrix {
+        let mut result = inputs.clone();
+        for (i, row) in result.data.chunks_mut(inputs.cols.max(1)).enumerate() {
+            for x in row {
+                *x = (*x - self.mean[i]) / self.std_dev[i];
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn idx(data_type: u8, dims: &[u32], data: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![0, 0, data_type, dims.len() as u8];
+        for d in dims {
+            bytes.extend_from_slice(&d.to_be_bytes());
+        }
+        bytes.extend_from_slice(data);
+        bytes
+    }
+
+    #[test]
+    fn parses_idx() {
+        let array = parse_idx(&idx(0x08, &[2, 1, 3], &[0, 1, 2, 3, 4, 255])).unwrap();
+
+        assert_eq!(array.dims, [2, 1, 3]);
+        assert_eq!(array.data, [0, 1, 2, 3, 4, 255]);
+    }
+
+    #[test]
+    fn rejects_bad_idx() {
+        for bytes in [
+            vec![1, 0, 8, 1, 0, 0, 0, 0],
+            idx(0x0D, &[1], &[0, 0, 0, 0]),
+            idx(0x08, &[4], &[1, 2, 3]),
+            vec![0, 0, 8, 2, 0, 0, 0, 1],
+        ] {
+            assert!(matches!(parse_idx(&bytes), Err(DataError::Idx(_))));
+        }
+    }
+
+    #[test]
+    fn parses_csv_with_label_column() {
+        let text = "label,a,b\n1,0.5,2\n0,-1,3\n\n2,4,5\n";
+        let options = CsvOptions {
+            label_column: Some(0),
+            ..Default::default()
+        };
+
+        let dataset = parse_csv(text, &options).unwrap();
+
+        assert_eq!(dataset.inputs.shape(), (2, 3));
+        assert_eq!(dataset.inputs.column(1), [-1.0, 3.0]);
+        assert_eq!(dataset.targets.shape(), (3, 3));
+        assert_eq!(dataset.targets.column(2), [0.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn csv_errors_name_the_line() {
+        let err = parse_csv("a,label\n1,0\nx,1\n", &CsvOptions::default()).unwrap_err();
+        assert!(matches!(err, DataError::Csv { line: 3, .. }), "{err}");
+
+        let err = parse_csv(
+            "1,0\n1,2,1\n",
+            &CsvOptions {
+                has_header: false,
+                ..Default::default()
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, DataError::Csv { line: 2, .. }), "{err}");
+
+        let options = CsvOptions {
+            classes: Some(2),
+            ..Default::default()
+        };
+        let err = parse_csv("a,label\n1,2\n", &options).unwrap_err();
+        assert!(matches!(
+            err,
+            DataError::LabelOutOfRange {
+                label: 2,
+                classes: 2
+            }
+        ));
+    }
+
+    #[test]
+    fn normalizer_standardizes_rows() {
+        let inputs = Matrix::from_columns(&[vec![1.0, 5.0], vec![3.0, 5.0]]);
+
+        let normalizer = Normalizer::fit(&inputs);
+        let normalized = normalizer.apply(&inputs);
+
+        assert_eq!(normalizer.mean, [2.0, 5.0]);
+        assert_eq!(normalized.data, [-1.0, 1.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn loads_fixtures() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures");
+
+        let train = load_idx(
+            dir.join("shapes-train-images.idx3-ubyte"),
+            dir.join("shapes-train-labels.idx1-ubyte"),
+            3,
+        )
+        .unwrap();
+        assert_eq!(train.inputs.rows, 36);
+        assert!(train.inputs.data.iter().all(|x| (0.0..=1.0).contains(x)));
+        assert!((0..train.len()).all(|j| train.targets.column(j).iter().sum::<f64>() == 1.0));
+
+        let blobs = load_csv(dir.join("blobs.csv"), &CsvOptions::default()).unwrap();
+        assert_eq!(blobs.inputs.rows, 4);
+        assert_eq!(blobs.targets.rows, 3);
+    }
+}
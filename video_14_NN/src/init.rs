@@ -0,0 +1,155 @@
+use rand::Rng;
+use rand_distr::{Distribution, Normal, Uniform};
+
+use crate::{Activation, Matrix, NetworkError};
+
+/// How a layer's weights are drawn. Biases always start at zero.
+///
+/// `fan_in` is the number of inputs of the layer and `fan_out` its number of neurons.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Initializer {
+    /// Uniform in `low..high`.
+    Uniform { low: f64, high: f64 },
+    /// Glorot: uniform in `±sqrt(6 / (fan_in + fan_out))`.
+    XavierUniform,
+    /// Glorot: normal with variance `2 / (fan_in + fan_out)`.
+    XavierNormal,
+    /// He: uniform in `±sqrt(6 / fan_in)`.
+    HeUniform,
+    /// He: normal with variance `2 / fan_in`.
+    HeNormal,
+}
+
+impl Initializer {
+    /// He for the ReLU family, Xavier for everything else.
+    pub fn for_activation(activation: Activation) -> Self {
+        match activation {
+            Activation::Relu | Activation::LeakyRelu(_) => Initializer::HeUniform,
+            _ => Initializer::XavierUniform,
+        }
+    }
+
+    /// A `fan_out x fan_in` weight matrix.
+    pub fn weights(&self, fan_in: usize, fan_out: usize, rng: &mut impl Rng) -> Matrix {
+        self.try_weights(fan_in, fan_out, rng)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Like [`Initializer::weights`], but fails instead of panicking when the
+    /// distribution is invalid: `Uniform` with `low >= high`, or a zero fan-in.
+    pub fn try_weights(
+        &self,
+        fan_in: usize,
+        fan_out: usize,
+        rng: &mut impl Rng,
+    ) -> Result<Matrix, NetworkError> {
+        let fan_sum = (fan_in + fan_out) as f64;
+        let fan_in_f = fan_in as f64;
+
+        let samples = fan_in * fan_out;
+        let data = match *self {
+            Initializer::Uniform { low, high } => sample_uniform(low, high, samples, rng),
+            Initializer::XavierUniform => {
+                let limit = (6.0 / fan_sum).sqrt();
+                sample_uniform(-limit, limit, samples, rng)
+            }
+            Initializer::XavierNormal => sample_normal((2.0 / fan_sum).sqrt(), samples, rng),
+            Initializer::HeUniform => {
+                let limit = (6.0 / fan_in_f).sqrt();
+                sample_uniform(-limit, limit, samples, rng)
+            }
+            Initializer::HeNormal => sample_normal((2.0 / fan_in_f).sqrt(), samples, rng),
+        }
+        .map_err(|err| {
+            NetworkError::Invalid(format!(
+                "{self:?} cannot initialize a {fan_out}x{fan_in} layer: {err}"
+            ))
+        })?;
+
+        Ok(Matrix {
+            rows: fan_out,
+            cols: fan_in,
+            data,
+        })
+    }
+}
+
+fn sample_uniform(low: f64, high: f64, len: usize, rng: &mut impl Rng) -> Result<Vec<f64>, String> {
+    let uniform = Uniform::new(low, high).map_err(|err| err.to_string())?;
+    Ok(uniform.sample_iter(rng).take(len).collect())
+}
+
+fn sample_normal(std_dev: f64, len: usize, rng: &mut impl Rng) -> Result<Vec<f64>, String> {
+    let normal = Normal::new(0.0, std_dev).map_err(|err| err.to_string())?;
+    Ok(normal.sample_iter(rng).take(len).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use super::*;
+
+    fn variance(data: &[f64]) -> f64 {
+        let mean = data.iter().sum::<f64>() / data.len() as f64;
+        data.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / data.len() as f64
+    }
+
+    #[test]
+    fn schemes_have_expected_variance() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let (fan_in, fan_out) = (200, 100);
+
+        for (initializer, expected) in [
+            (Initializer::XavierUniform, 2.0 / 300.0),
+            (Initializer::XavierNormal, 2.0 / 300.0),
+            (Initializer::HeUniform, 2.0 / 200.0),
+            (Initializer::HeNormal, 2.0 / 200.0),
+        ] {
+            let weights = initializer.weights(fan_in, fan_out, &mut rng);
+
+            assert_eq!((weights.rows, weights.cols), (fan_out, fan_in));
+            let variance = variance(&weights.data);
+            assert!(
+                (variance - expected).abs() < 0.05 * expected,
+                "{initializer:?}: variance {variance}, expected {expected}"
+            );
+        }
+    }
+
+    #[test]
+    fn uniform_stays_in_bounds() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let limit = (6.0f64 / 30.0).sqrt();
+
+        let xavier = Initializer::XavierUniform.weights(10, 20, &mut rng);
+        let uniform = Initializer::Uniform {
+            low: 0.0,
+            high: 1.0,
+        }
+        .weights(10, 20, &mut rng);
+
+        assert!(xavier.data.iter().all(|v| v.abs() < limit));
+        assert!(uniform.data.iter().all(|v| (0.0..1.0).contains(v)));
+    }
+
+    #[test]
+    fn same_seed_same_weights() {
+        let a = Initializer::HeNormal.weights(4, 3, &mut StdRng::seed_from_u64(9));
+        let b = Initializer::HeNormal.weights(4, 3, &mut StdRng::seed_from_u64(9));
+
+        assert_eq!(a.data, b.data);
+    }
+
+    #[test]
+    fn invalid_distributions_are_errors() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let empty_range = Initializer::Uniform {
+            low: 1.0,
+            high: 1.0,
+        };
+
+        assert!(empty_range.try_weights(2, 3, &mut rng).is_err());
+        assert!(Initializer::HeNormal.try_weights(0, 3, &mut rng).is_err());
+    }
+}
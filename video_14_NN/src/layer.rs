@@ -0,0 +1,281 @@
+use rand::Rng;
+
+use crate::Matrix;
+
+/// Training enables dropout and batch statistics, evaluation makes
+/// [`Network::feed_forward`](crate::Network::feed_forward) deterministic.
+///
+/// Networks start in evaluation mode; [`Network::fit`](crate::Network::fit) switches
+/// to training mode for its own passes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Mode {
+    Train,
+    #[default]
+    Eval,
+}
+
+/// A layer inserted after a dense layer's activation, before the next dense layer.
+#[derive(Debug, Clone)]
+pub enum Layer {
+    Dropout(Dropout),
+    BatchNorm(BatchNorm),
+}
+
+impl Layer {
+    pub fn forward(&mut self, inputs: &Matrix, mode: Mode, rng: &mut impl Rng) -> Matrix {
+        match self {
+            Layer::Dropout(dropout) => dropout.forward(inputs, mode, rng),
+            Layer::BatchNorm(batch_norm) => batch_norm.forward(inputs, mode),
+        }
+    }
+
+    /// Gradient w.r.t. the inputs of the last `forward` call, and gradients of
+    /// [`Layer::parameters`] in the same order.
+    pub fn backward(&self, output_gradients: &Matrix) -> (Matrix, Vec<Matrix>) {
+        match self {
+            Layer::Dropout(dropout) => (dropout.backward(output_gradients), vec![]),
+            Layer::BatchNorm(batch_norm) => batch_norm.backward(output_gradients),
+        }
+    }
+
+    pub fn parameters(&self) -> Vec<&Matrix> {
+        match self {
+            Layer::Dropout(_) => vec![],
+            Layer::BatchNorm(batch_norm) => vec![&batch_norm.scale, &batch_norm.shift],
+        }
+    }
+
+    pub fn parameters_mut(&mut self) -> Vec<&mut Matrix> {
+        match self {
+            Layer::Dropout(_) => vec![],
+            Layer::BatchNorm(batch_norm) => vec![&mut batch_norm.scale, &mut batch_norm.shift],
+        }
+    }
+}
+
+/// Inverted dropout: zeroes each value with probability `rate` while training and
+/// scales the rest by `1 / (1 - rate)`, so evaluation is the identity.
+#[derive(Debug, Clone)]
+pub struct Dropout {
+    pub rate: f64,
+    /// Per-value factor of the last training pass, `None` after an evaluation pass.
+    mask: Option<Matrix>,
+}
+
+impl Dropout {
+    pub fn new(rate: f64) -> Self {
+        assert!((0.0..1.0).contains(&rate), "Dropout rate must be in 0..1");
+        Dropout { rate, mask: None }
+    }
+
+    fn forward(&mut self, inputs: &Matrix, mode: Mode, rng: &mut impl Rng) -> Matrix {
+        if mode == Mode::Eval || self.rate == 0.0 {
+            self.mask = None;
+            return inputs.clone();
+        }
+
+        let keep = 1.0 - self.rate;
+        let mask = Matrix {
+            rows: inputs.rows,
+            cols: inputs.cols,
+            data: (0..inputs.data.len())
+                .map(|_| {
+                    if rng.random::<f64>() < keep {
+                        1.0 / keep
+                    } else {
+                        0.0
+                    }
+                })
+                .collect(),
+        };
+        let outputs = inputs.elementwise_multiply(&mask);
+        self.mask = Some(mask);
+        outputs
+    }
+
+    fn backward(&self, output_gradients: &Matrix) -> Matrix {
+        match &self.mask {
+            Some(mask) => output_gradients.elementwise_multiply(mask),
+            None => output_gradients.clone(),
+        }
+    }
+}
+
+/// Normalizes every feature (row) over the batch, then applies a learnable `scale`
+/// and `shift`.
+///
+/// Evaluation uses the running mean and (biased) variance collected while training.
+#[derive(Debug, Clone)]
+pub struct BatchNorm {
+    pub scale: Matrix,
+    pub shift: Matrix,
+    pub running_mean: Vec<f64>,
+    pub running_variance: Vec<f64>,
+    /// Weight of the old running statistics in each update.
+    pub momentum: f64,
+    pub epsilon: f64,
+    cache: Option<BatchNormCache>,
+}
+
+#[derive(Debug, Clone)]
+struct BatchNormCache {
+    normalized: Matrix,
+    inv_std: Vec<f64>,
+    mode: Mode,
+}
+
+impl BatchNorm {
+    pub fn new(features: usize) -> Self {
+        BatchNorm {
+            scale: Matrix::from(vec![1.0; features]),
+            shift: Matrix::from(vec![0.0; features]),
+            running_mean: vec![0.0; features],
+            running_variance: vec![1.0; features],
+            momentum: 0.9,
+            epsilon: 1e-5,
+            cache: None,
+        }
+    }
+
+    pub fn features(&self) -> usize {
+        self.scale.rows
+    }
+
+    fn forward(&mut self, inputs: &Matrix, mode: Mode) -> Matrix {
+        assert_eq!(
+            inputs.rows,
+            self.features(),
+            "Batch norm expects one row per feature"
+        );
+        let n = inputs.cols.max(1) as f64;
+
+        let (mean, variance) = match mode {
+            Mode::Train => {
+                let mean: Vec<f64> = rows(inputs)
+                    .map(|row| row.iter().sum::<f64>() / n)
+                    .collect();
+                let variance: Vec<f64> = rows(inputs)
+                    .zip(&mean)
+                    .map(|(row, m)| row.iter().map(|x| (x - m).powi(2)).sum::<f64>() / n)
+                    .collect();
+
+                for i in 0..self.features() {
+                    self.running_mean[i] =
+                        self.momentum * self.running_mean[i] + (1.0 - self.momentum) * mean[i];
+                    self.running_variance[i] = self.momentum * self.running_variance[i]
+                        + (1.0 - self.momentum) * variance[i];
+                }
+                (mean, variance)
+            }
+            Mode::Eval => (self.running_mean.clone(), self.running_variance.clone()),
+        };
+
+        let inv_std: Vec<f64> = variance
+            .iter()
+            .map(|v| 1.0 / (v + self.epsilon).sqrt())
+            .collect();
+        let mut normalized = inputs.clone();
+        let mut outputs = inputs.clone();
+        for (i, (norm_row, out_row)) in rows_mut(&mut normalized)
+            .zip(rows_mut(&mut outputs))
+            .enumerate()
+        {
+            for (x_hat, y) in norm_row.iter_mut().zip(out_row) {
+                *x_hat = (*x_hat - mean[i]) * inv_std[i];
+                *y = self.scale.data[i] * *x_hat + self.shift.data[i];
+            }
+        }
+
+        self.cache = Some(BatchNormCache {
+            normalized,
+            inv_std,
+            mode,
+        });
+        outputs
+    }
+
+    fn backward(&self, output_gradients: &Matrix) -> (Matrix, Vec<Matrix>) {
+        let cache = self.cache.as_ref().expect("backward called before forward");
+        let n = output_gradients.cols.max(1) as f64;
+
+        let mut input_gradients = output_gradients.clone();
+        let mut scale_gradients = vec![0.0; self.features()];
+        let mut shift_gradients = vec![0.0; self.features()];
+
+        for (i, (grad_row, norm_row)) in rows_mut(&mut input_gradients)
+            .zip(rows(&cache.normalized))
+            .enumerate()
+        {
+            let sum: f64 = grad_row.iter().sum();
+            let sum_normalized: f64 = grad_row.iter().zip(norm_row).map(|(g, x)| g * x).sum();
+            shift_gradients[i] = sum;
+            scale_gradients[i] = sum_normalized;
+
+            let factor = self.scale.data[i] * cache.inv_std[i];
+            for (g, x_hat) in grad_row.iter_mut().zip(norm_row) {
+                *g = match cache.mode {
+                    // The batch statistics depend on every input as well.
+                    Mode::Train => factor * (*g - sum / n - x_hat * sum_normalized / n),
+                    Mode::Eval => factor * *g,
+                };
+            }
+        }
+
+        (
+            input_gradients,
+            vec![Matrix::from(scale_gradients), Matrix::from(shift_gradients)],
+        )
+    }
+}
+
+fn rows(matrix: &Matrix) -> impl Iterator<Item = &[f64]> {
+    matrix.data.chunks(matrix.cols.max(1)).take(matrix.rows)
+}
+
+fn rows_mut(matrix: &mut Matrix) -> impl Iterator<Item = &mut [f64]> {
+    let rows = matrix.rows;
+    matrix.data.chunks_mut(matrix.cols.max(1)).take(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use super::*;
+
+    #[test]
+    fn dropout_is_identity_in_eval_mode() {
+        let mut dropout = Layer::Dropout(Dropout::new(0.5));
+        let inputs = Matrix::from(vec![1.0; 100]);
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let eval = dropout.forward(&inputs, Mode::Eval, &mut rng);
+        let train = dropout.forward(&inputs, Mode::Train, &mut rng);
+
+        assert_eq!(eval.data, inputs.data);
+        assert!(train.data.iter().all(|&x| x == 0.0 || x == 2.0));
+        let kept = train.data.iter().filter(|&&x| x > 0.0).count();
+        assert!((30..70).contains(&kept), "kept {kept}");
+
+        let (gradients, _) = dropout.backward(&Matrix::from(vec![1.0; 100]));
+        assert_eq!(gradients.data, train.data);
+    }
+
+    #[test]
+    fn batch_norm_normalizes_and_tracks_statistics() {
+        let mut batch_norm = BatchNorm::new(2);
+        let inputs = Matrix::from_columns(&[vec![1.0, 10.0], vec![3.0, 10.0], vec![5.0, 10.0]]);
+
+        let outputs = batch_norm.forward(&inputs, Mode::Train);
+
+        let first: Vec<f64> = outputs.data[..3].to_vec();
+        assert!(first.iter().sum::<f64>().abs() < 1e-12);
+        assert!((first.iter().map(|x| x * x).sum::<f64>() / 3.0 - 1.0).abs() < 1e-4);
+        assert_eq!(&outputs.data[3..], [0.0; 3]);
+        assert!((batch_norm.running_mean[0] - 0.3).abs() < 1e-12);
+        assert!((batch_norm.running_mean[1] - 1.0).abs() < 1e-12);
+
+        let eval = batch_norm.forward(&Matrix::from(vec![0.3, 1.0]), Mode::Eval);
+        assert!(eval.data.iter().all(|x| x.abs() < 1e-12));
+    }
+}
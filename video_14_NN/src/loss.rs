@@ -0,0 +1,112 @@
+use crate::Matrix;
+
+const EPSILON: f64 = 1e-12;
+
+/// Loss between network outputs and targets.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Loss {
+    /// Averaged over every output of every sample.
+    MeanSquaredError,
+    /// Averaged over every output of every sample.
+    BinaryCrossEntropy,
+    /// Summed over the classes (rows), averaged over the samples (columns).
+    ///
+    /// Expects one-hot (or probability) targets; pair it with a softmax output layer.
+    CategoricalCrossEntropy,
+}
+
+impl Loss {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Loss::MeanSquaredError => "mean_squared_error",
+            Loss::BinaryCrossEntropy => "binary_cross_entropy",
+            Loss::CategoricalCrossEntropy => "categorical_cross_entropy",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Loss> {
+        match name {
+            "mean_squared_error" => Some(Loss::MeanSquaredError),
+            "binary_cross_entropy" => Some(Loss::BinaryCrossEntropy),
+            "categorical_cross_entropy" => Some(Loss::CategoricalCrossEntropy),
+            _ => None,
+        }
+    }
+
+    pub fn loss(&self, outputs: &Matrix, targets: &Matrix) -> f64 {
+        assert_same_shape(outputs, targets);
+        let pairs = outputs.data.iter().zip(&targets.data);
+
+        match self {
+            Loss::MeanSquaredError => {
+                pairs.map(|(y, t)| (y - t).powi(2)).sum::<f64>() / outputs.data.len() as f64
+            }
+            Loss::BinaryCrossEntropy => {
+                let sum: f64 = pairs
+                    .map(|(&y, t)| {
+                        let y = y.clamp(EPSILON, 1.0 - EPSILON);
+                        t * y.ln() + (1.0 - t) * (1.0 - y).ln()
+                    })
+                    .sum();
+                -sum / outputs.data.len() as f64
+            }
+            Loss::CategoricalCrossEntropy => {
+                let sum: f64 = pairs.map(|(&y, t)| t * y.max(EPSILON).ln()).sum();
+                -sum / outputs.cols as f64
+            }
+        }
+    }
+
+    /// Gradient of [`Loss::loss`] w.r.t. the outputs.
+    pub fn gradient(&self, outputs: &Matrix, targets: &Matrix) -> Matrix {
+        assert_same_shape(outputs, targets);
+        let pairs = outputs.data.iter().zip(&targets.data);
+
+        let data = match self {
+            Loss::MeanSquaredError => {
+                let n = outputs.data.len() as f64;
+                pairs.map(|(y, t)| 2.0 * (y - t) / n).collect()
+            }
+            Loss::BinaryCrossEntropy => {
+                let n = outputs.data.len() as f64;
+                pairs
+                    .map(|(&y, t)| {
+                        let y = y.clamp(EPSILON, 1.0 - EPSILON);
+                        (y - t) / (y * (1.0 - y)) / n
+                    })
+                    .collect()
+            }
+            Loss::CategoricalCrossEntropy => {
+                let n = outputs.cols as f64;
+                pairs.map(|(&y, t)| -t / y.max(EPSILON) / n).collect()
+            }
+        };
+
+        Matrix {
+            rows: outputs.rows,
+            cols: outputs.cols,
+            data,
+        }
+    }
+
+    /// Gradient w.r.t. the softmax *inputs* when this loss follows a softmax layer.
+    ///
+    /// For categorical cross-entropy it collapses to `(outputs - targets) / n`, which
+    /// avoids dividing by tiny probabilities.
+    pub(crate) fn softmax_gradient(&self, outputs: &Matrix, targets: &Matrix) -> Option<Matrix> {
+        match self {
+            Loss::CategoricalCrossEntropy => {
+                Some(outputs.subtract(targets).scale(1.0 / outputs.cols as f64))
+            }
+            _ => None,
+        }
+    }
+}
+
+fn assert_same_shape(outputs: &Matrix, targets: &Matrix) {
+    assert_eq!(
+        (outputs.rows, outputs.cols),
+        (targets.rows, targets.cols),
+        "Outputs and targets must have the same shape"
+    );
+}
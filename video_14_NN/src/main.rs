@@ -1,10 +1,6 @@
-use std::f64::consts::E;
 use video_14_NN::*;
 
-pub const SIGMOID: Activation = Activation {
-    function: |x| 1.0 / (1.0 + E.powf(-x)),
-    derivative: |x| x * (1.0 - x),
-};
+const MODEL_PATH: &str = "xor_network.json";
 
 fn main() {
     let inputs = vec![
@@ -14,13 +10,54 @@ fn main() {
         vec![1.0, 1.0],
     ];
     let targets = vec![vec![0.0], vec![1.0], vec![0.0], vec![1.0]];
+    let dataset = Dataset::from_samples(&inputs, &targets);
 
-    let mut network = Network::new(vec![2, 3, 1], SIGMOID, 0.5);
-
-    network.train(inputs, targets, 100000);
+    let mut network = match Network::load(MODEL_PATH) {
+        Ok(network) => {
+            println!("Loaded trained network from {MODEL_PATH}");
+            network
+        }
+        Err(err) => {
+            println!("No saved network ({err}), training a new one");
+            train(&dataset)
+        }
+    };
 
     println!("{:?}", network.feed_forward(Matrix::from(vec![0.0, 0.0])));
     println!("{:?}", network.feed_forward(Matrix::from(vec![0.0, 1.0])));
     println!("{:?}", network.feed_forward(Matrix::from(vec![1.0, 0.0])));
     println!("{:?}", network.feed_forward(Matrix::from(vec![1.0, 1.0])));
 }
+
+fn train(dataset: &Dataset) -> Network {
+    let mut network = Network::builder(2)
+        .layer(3, Activation::Sigmoid)
+        .layer(1, Activation::Sigmoid)
+        .learning_rate(0.5)
+        .seed(42)
+        .build();
+
+    let epochs = 100000;
+    let config = TrainConfig {
+        epochs,
+        batch_size: 1,
+        shuffle: false,
+        ..Default::default()
+    };
+
+    network.fit(dataset, None, &config, |metrics| {
+        if metrics.epoch % (epochs / 100) == 0 {
+            println!(
+                "Epoch {} of {epochs}, loss {:.6}",
+                metrics.epoch, metrics.loss
+            );
+        }
+    });
+
+    match network.save(MODEL_PATH, ModelFormat::Json) {
+        Ok(()) => println!("Saved trained network to {MODEL_PATH}"),
+        Err(err) => eprintln!("Could not save network: {err}"),
+    }
+
+    network
+}
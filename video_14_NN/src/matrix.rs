@@ -0,0 +1,555 @@
+use std::{
+    fmt,
+    ops::{Add, Div, Mul, Neg, Sub},
+};
+
+use rand::Rng;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MatrixError {
+    /// `op` does not accept operands of these `(rows, cols)` shapes.
+    ShapeMismatch {
+        op: &'static str,
+        left: (usize, usize),
+        right: (usize, usize),
+    },
+}
+
+impl MatrixError {
+    fn mismatch(op: &'static str, left: &Matrix, right: &Matrix) -> Self {
+        MatrixError::ShapeMismatch {
+            op,
+            left: left.shape(),
+            right: right.shape(),
+        }
+    }
+}
+
+impl fmt::Display for MatrixError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MatrixError::ShapeMismatch { op, left, right } => write!(
+                f,
+                "{op}: incompatible shapes {}x{} and {}x{}",
+                left.0, left.1, right.0, right.1
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MatrixError {}
+
+/// The panicking ops are thin wrappers over their `try_*` versions.
+fn expect_shape<T>(result: Result<T, MatrixError>) -> T {
+    result.unwrap_or_else(|err| panic!("{err}"))
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Matrix {
+    pub rows: usize,
+    pub cols: usize,
+    pub data: Vec<f64>,
+}
+
+impl Matrix {
+    /// Uniform in `0..1`, drawn from `rng` so a seeded generator gives the same matrix.
+    pub fn get_random(rows: usize, cols: usize, rng: &mut impl Rng) -> Self {
+        let mut buffer = Vec::with_capacity(rows * cols);
+
+        for _ in 0..rows * cols {
+            buffer.push(rng.random_range(0.0..1.0));
+        }
+        Matrix {
+            rows,
+            cols,
+            data: buffer,
+        }
+    }
+
+    pub fn add(&self, other: &Matrix) -> Matrix {
+        expect_shape(self.try_add(other))
+    }
+
+    pub fn try_add(&self, other: &Matrix) -> Result<Matrix, MatrixError> {
+        self.zip_with(other, "add", |a, b| a + b)
+    }
+
+    pub fn subtract(&self, other: &Matrix) -> Matrix {
+        expect_shape(self.try_subtract(other))
+    }
+
+    pub fn try_subtract(&self, other: &Matrix) -> Result<Matrix, MatrixError> {
+        self.zip_with(other, "subtract", |a, b| a - b)
+    }
+
+    pub fn elementwise_multiply(&self, other: &Matrix) -> Matrix {
+        expect_shape(self.try_elementwise_multiply(other))
+    }
+
+    pub fn try_elementwise_multiply(&self, other: &Matrix) -> Result<Matrix, MatrixError> {
+        self.zip_with(other, "elementwise_multiply", |a, b| a * b)
+    }
+
+    pub fn dot_multiply(&self, other: &Matrix) -> Matrix {
+        expect_shape(self.try_dot_multiply(other))
+    }
+
+    pub fn try_dot_multiply(&self, other: &Matrix) -> Result<Matrix, MatrixError> {
+        let mut result = Matrix::default();
+        self.try_dot_multiply_into(other, &mut result)?;
+        Ok(result)
+    }
+
+    /// Writes `self * other` into `out`, reusing its allocation. `other` is still
+    /// packed into a temporary transposed copy on every call.
+    pub fn dot_multiply_into(&self, other: &Matrix, out: &mut Matrix) {
+        expect_shape(self.try_dot_multiply_into(other, out))
+    }
+
+    pub fn try_dot_multiply_into(
+        &self,
+        other: &Matrix,
+        out: &mut Matrix,
+    ) -> Result<(), MatrixError> {
+        if self.cols != other.rows {
+            return Err(MatrixError::mismatch("dot_multiply", self, other));
+        }
+
+        out.rows = self.rows;
+        out.cols = other.cols;
+        out.data.clear();
+        out.data.resize(self.rows * other.cols, 0.0);
+        if out.data.is_empty() {
+            return Ok(());
+        }
+
+        // Columns of `other` become contiguous rows, so every output element is a
+        // dot product of two contiguous slices.
+        let packed = other.transpose();
+
+        #[cfg(feature = "rayon")]
+        if self.rows * self.cols * other.cols >= PARALLEL_THRESHOLD {
+            use rayon::prelude::*;
+
+            out.data
+                .par_chunks_mut(other.cols * ROW_BLOCK)
+                .enumerate()
+                .for_each(|(block, out)| multiply_rows(self, &packed, block * ROW_BLOCK, out));
+            return Ok(());
+        }
+
+        multiply_rows(self, &packed, 0, &mut out.data);
+        Ok(())
+    }
+
+    fn zip_with(
+        &self,
+        other: &Matrix,
+        op: &'static str,
+        func: impl Fn(f64, f64) -> f64,
+    ) -> Result<Matrix, MatrixError> {
+        if self.shape() != other.shape() {
+            return Err(MatrixError::mismatch(op, self, other));
+        }
+
+        Ok(Matrix {
+            rows: self.rows,
+            cols: self.cols,
+            data: self
+                .data
+                .iter()
+                .zip(&other.data)
+                .map(|(&a, &b)| func(a, b))
+                .collect(),
+        })
+    }
+
+    /// `(rows, cols)`
+    pub fn shape(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    pub fn transpose(&self) -> Matrix {
+        let mut buffer = vec![0.0; self.cols * self.rows];
+
+        for i in 0..self.rows {
+            for j in 0..self.cols {
+                buffer[j * self.rows + i] = self.data[i * self.cols + j];
+            }
+        }
+
+        Matrix {
+            rows: self.cols,
+            cols: self.rows,
+            data: buffer,
+        }
+    }
+
+    pub fn scale(&self, factor: f64) -> Matrix {
+        Matrix {
+            rows: self.rows,
+            cols: self.cols,
+            data: self.data.iter().map(|x| x * factor).collect(),
+        }
+    }
+
+    /// Sums every row across its columns, giving a `rows x 1` matrix.
+    pub fn sum_cols(&self) -> Matrix {
+        let data = self
+            .data
+            .chunks(self.cols)
+            .map(|row| row.iter().sum())
+            .collect();
+
+        Matrix {
+            rows: self.rows,
+            cols: 1,
+            data,
+        }
+    }
+
+    /// Adds the `rows x 1` matrix `column` to every column of `self`.
+    pub fn add_column(&self, column: &Matrix) -> Matrix {
+        expect_shape(self.try_add_column(column))
+    }
+
+    pub fn try_add_column(&self, column: &Matrix) -> Result<Matrix, MatrixError> {
+        let mut result = self.clone();
+        result.try_add_column_in_place(column)?;
+        Ok(result)
+    }
+
+    pub fn add_column_in_place(&mut self, column: &Matrix) {
+        expect_shape(self.try_add_column_in_place(column))
+    }
+
+    pub fn try_add_column_in_place(&mut self, column: &Matrix) -> Result<(), MatrixError> {
+        if column.shape() != (self.rows, 1) {
+            return Err(MatrixError::mismatch("add_column", self, column));
+        }
+        if self.cols == 0 {
+            return Ok(());
+        }
+
+        for (row, c) in self.data.chunks_mut(self.cols).zip(&column.data) {
+            for x in row {
+                *x += c;
+            }
+        }
+        Ok(())
+    }
+
+    /// New matrix made of the given columns of `self`, in that order.
+    pub fn select_cols(&self, indices: &[usize]) -> Matrix {
+        let mut data = Vec::with_capacity(self.rows * indices.len());
+        for i in 0..self.rows {
+            data.extend(indices.iter().map(|&j| self.data[i * self.cols + j]));
+        }
+
+        Matrix {
+            rows: self.rows,
+            cols: indices.len(),
+            data,
+        }
+    }
+
+    pub fn column(&self, j: usize) -> Vec<f64> {
+        (0..self.rows)
+            .map(|i| self.data[i * self.cols + j])
+            .collect()
+    }
+
+    /// Builds a matrix whose columns are the given vectors (one sample per column).
+    pub fn from_columns(columns: &[Vec<f64>]) -> Matrix {
+        let rows = columns.first().map_or(0, Vec::len);
+        assert!(
+            columns.iter().all(|c| c.len() == rows),
+            "All columns must have the same length"
+        );
+
+        let mut data = Vec::with_capacity(rows * columns.len());
+        for i in 0..rows {
+            data.extend(columns.iter().map(|c| c[i]));
+        }
+
+        Matrix {
+            rows,
+            cols: columns.len(),
+            data,
+        }
+    }
+
+    pub fn map(&self, func: impl Fn(f64) -> f64) -> Matrix {
+        Matrix {
+            rows: self.rows,
+            cols: self.cols,
+            data: self.data.iter().map(|&x| func(x)).collect(),
+        }
+    }
+
+    pub fn map_in_place(&mut self, func: impl Fn(f64) -> f64) {
+        for x in &mut self.data {
+            *x = func(*x);
+        }
+    }
+}
+
+/// Output columns per tile of the blocked matrix product.
+const COL_BLOCK: usize = 64;
+
+/// Inner (shared) dimension per tile: a `COL_BLOCK x INNER_BLOCK` tile of the packed
+/// operand is 64 KB, small enough to stay in cache.
+const INNER_BLOCK: usize = 128;
+
+/// Output rows per task when the product is split across threads.
+#[cfg(feature = "rayon")]
+const ROW_BLOCK: usize = 64;
+
+/// Multiply-adds above which the product is split across threads.
+#[cfg(feature = "rayon")]
+const PARALLEL_THRESHOLD: usize = 1 << 18;
+
+/// Fills `out`, which holds consecutive output rows starting at `first_row`, with
+/// rows of `a * b` where `packed` is `b` transposed. `out` must be zeroed.
+///
+/// The product is tiled over both the output columns and the inner dimension, so
+/// each tile of `packed` is reused for every row of `out` while it is still in
+/// cache. Every output accumulates its terms in inner-dimension order.
+fn multiply_rows(a: &Matrix, packed: &Matrix, first_row: usize, out: &mut [f64]) {
+    let (inner, cols) = (a.cols, packed.rows);
+
+    for tile in (0..cols).step_by(COL_BLOCK) {
+        let tile_end = (tile + COL_BLOCK).min(cols);
+
+        for k in (0..inner).step_by(INNER_BLOCK) {
+            let k_end = (k + INNER_BLOCK).min(inner);
+
+            for (r, out_row) in out.chunks_mut(cols).enumerate() {
+                let a_row = &a.data[(first_row + r) * inner..][k..k_end];
+
+                for (out, j) in out_row[tile..tile_end].iter_mut().zip(tile..) {
+                    let b_col = &packed.data[j * inner..][k..k_end];
+                    *out = a_row
+                        .iter()
+                        .zip(b_col)
+                        .fold(*out, |acc, (x, y)| acc + x * y);
+                }
+            }
+        }
+    }
+}
+
+impl From<Vec<f64>> for Matrix {
+    fn from(vec: Vec<f64>) -> Self {
+        let rows = vec.len();
+        let cols = 1;
+        Matrix {
+            rows,
+            cols,
+            data: vec,
+        }
+    }
+}
+
+impl Add for &Matrix {
+    type Output = Matrix;
+
+    fn add(self, other: &Matrix) -> Matrix {
+        Matrix::add(self, other)
+    }
+}
+
+impl Sub for &Matrix {
+    type Output = Matrix;
+
+    fn sub(self, other: &Matrix) -> Matrix {
+        self.subtract(other)
+    }
+}
+
+/// Matrix product, see [`Matrix::elementwise_multiply`] for the Hadamard product.
+impl Mul for &Matrix {
+    type Output = Matrix;
+
+    fn mul(self, other: &Matrix) -> Matrix {
+        self.dot_multiply(other)
+    }
+}
+
+impl Add<f64> for &Matrix {
+    type Output = Matrix;
+
+    fn add(self, scalar: f64) -> Matrix {
+        self.map(|x| x + scalar)
+    }
+}
+
+impl Sub<f64> for &Matrix {
+    type Output = Matrix;
+
+    fn sub(self, scalar: f64) -> Matrix {
+        self.map(|x| x - scalar)
+    }
+}
+
+impl Mul<f64> for &Matrix {
+    type Output = Matrix;
+
+    fn mul(self, scalar: f64) -> Matrix {
+        self.scale(scalar)
+    }
+}
+
+impl Mul<&Matrix> for f64 {
+    type Output = Matrix;
+
+    fn mul(self, matrix: &Matrix) -> Matrix {
+        matrix.scale(self)
+    }
+}
+
+impl Div<f64> for &Matrix {
+    type Output = Matrix;
+
+    fn div(self, scalar: f64) -> Matrix {
+        self.map(|x| x / scalar)
+    }
+}
+
+impl Neg for &Matrix {
+    type Output = Matrix;
+
+    fn neg(self) -> Matrix {
+        self.map(|x| -x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matrix(rows: usize, cols: usize, data: &[f64]) -> Matrix {
+        Matrix {
+            rows,
+            cols,
+            data: data.to_vec(),
+        }
+    }
+
+    #[test]
+    fn shape_errors() {
+        let a = matrix(2, 3, &[1.0; 6]);
+        let b = matrix(2, 2, &[1.0; 4]);
+
+        assert_eq!(
+            a.try_add(&b).unwrap_err(),
+            MatrixError::ShapeMismatch {
+                op: "add",
+                left: (2, 3),
+                right: (2, 2),
+            }
+        );
+        assert!(a.try_subtract(&b).is_err());
+        assert!(a.try_elementwise_multiply(&b).is_err());
+        assert!(a.try_dot_multiply(&b).is_err());
+        assert!(b.try_dot_multiply(&a).is_ok());
+        assert!(
+            a.try_add_column(&Matrix::from(vec![1.0, 2.0, 3.0]))
+                .is_err()
+        );
+        assert_eq!(
+            a.try_dot_multiply(&b).unwrap_err().to_string(),
+            "dot_multiply: incompatible shapes 2x3 and 2x2"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "subtract: incompatible shapes")]
+    fn panicking_ops_report_shapes() {
+        matrix(1, 2, &[1.0, 2.0]).subtract(&matrix(2, 1, &[1.0, 2.0]));
+    }
+
+    #[test]
+    fn operators() {
+        let a = matrix(2, 2, &[1.0, 2.0, 3.0, 4.0]);
+        let b = matrix(2, 2, &[0.5, 0.0, 0.0, 2.0]);
+
+        assert_eq!((&a + &b).data, [1.5, 2.0, 3.0, 6.0]);
+        assert_eq!((&a - &b).data, [0.5, 2.0, 3.0, 2.0]);
+        assert_eq!((&a * &b).data, [0.5, 4.0, 1.5, 8.0]);
+        assert_eq!((&a * 2.0).data, (2.0 * &a).data);
+        assert_eq!((&a + 1.0).data, [2.0, 3.0, 4.0, 5.0]);
+        assert_eq!((&a - 1.0).data, [0.0, 1.0, 2.0, 3.0]);
+        assert_eq!((&a / 2.0).data, [0.5, 1.0, 1.5, 2.0]);
+        assert_eq!((-&a).data, [-1.0, -2.0, -3.0, -4.0]);
+    }
+
+    fn naive_dot(a: &Matrix, b: &Matrix) -> Matrix {
+        let mut data = vec![0.0; a.rows * b.cols];
+        for i in 0..a.rows {
+            for j in 0..b.cols {
+                for k in 0..a.cols {
+                    data[i * b.cols + j] += a.data[i * a.cols + k] * b.data[k * b.cols + j];
+                }
+            }
+        }
+        matrix(a.rows, b.cols, &data)
+    }
+
+    fn sequence(rows: usize, cols: usize) -> Matrix {
+        let data: Vec<f64> = (0..rows * cols)
+            .map(|i| ((i * 37) % 101) as f64 / 50.0 - 1.0)
+            .collect();
+        matrix(rows, cols, &data)
+    }
+
+    #[test]
+    fn blocked_product_matches_naive() {
+        // Sizes around the tile widths and large enough for the parallel path.
+        for (n, k, m) in [
+            (1, 1, 1),
+            (3, 5, 2),
+            (65, 63, 130),
+            (200, 150, 170),
+            (4, 0, 3),
+        ] {
+            let a = sequence(n, k);
+            let b = sequence(k, m);
+
+            let product = a.dot_multiply(&b);
+
+            assert_eq!(product.shape(), (n, m));
+            assert_eq!(product.data, naive_dot(&a, &b).data, "{n}x{k} * {k}x{m}");
+        }
+    }
+
+    #[test]
+    fn in_place_variants() {
+        let a = sequence(3, 4);
+        let b = sequence(4, 2);
+        let mut out = sequence(7, 7);
+
+        a.dot_multiply_into(&b, &mut out);
+        assert_eq!(out.data, a.dot_multiply(&b).data);
+        assert_eq!(out.shape(), (3, 2));
+
+        let column = Matrix::from(vec![1.0, 2.0, 3.0]);
+        let expected = out.add_column(&column);
+        out.add_column_in_place(&column);
+        assert_eq!(out.data, expected.data);
+
+        out.map_in_place(|x| x * 2.0);
+        assert_eq!(out.data, expected.scale(2.0).data);
+
+        assert!(a.try_dot_multiply_into(&a, &mut out).is_err());
+    }
+
+    #[test]
+    fn map_takes_closures() {
+        let a = matrix(1, 3, &[1.0, 2.0, 3.0]);
+        let offset = 10.0;
+
+        assert_eq!(a.map(|x| x + offset).data, [11.0, 12.0, 13.0]);
+        assert_eq!(a.data, [1.0, 2.0, 3.0]);
+    }
+}
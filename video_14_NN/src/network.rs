@@ -0,0 +1,968 @@
+use std::fmt;
+
+use rand::{SeedableRng, rngs::StdRng};
+
+use crate::{
+    Activation, BatchNorm, Dataset, Dropout, History, Initializer, Layer, LearningRateSchedule,
+    Loss, Matrix, Mode, Optimizer, Sgd, TrainConfig,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum NetworkError {
+    /// The builder's settings cannot make a network.
+    Invalid(String),
+    /// The inputs have `found` rows where the network has `expected` inputs.
+    InputSize { expected: usize, found: usize },
+}
+
+impl fmt::Display for NetworkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NetworkError::Invalid(msg) => write!(f, "invalid network: {msg}"),
+            NetworkError::InputSize { expected, found } => {
+                write!(
+                    f,
+                    "inputs have {found} rows, the network expects {expected}"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for NetworkError {}
+
+pub struct Network {
+    layers: Vec<usize>,
+    weights: Vec<Matrix>,
+    biases: Vec<Matrix>,
+    /// `inserted[i]` runs after dense layer `i`'s activation.
+    inserted: Vec<Vec<Layer>>,
+    /// Inputs of every dense layer, then the network outputs.
+    data: Vec<Matrix>,
+    /// Activation outputs of the dense layers followed by inserted layers.
+    activated: Vec<Matrix>,
+    activations: Vec<Activation>,
+    mode: Mode,
+    loss: Loss,
+    learning_rate: f64,
+    optimizer: Box<dyn Optimizer>,
+    weight_decay: f64,
+    schedule: LearningRateSchedule,
+    epoch: u32,
+    rng: StdRng,
+}
+
+/// Loss gradients for every layer, in the same order as the network's weights.
+#[derive(Debug, Clone)]
+pub struct Gradients {
+    pub weights: Vec<Matrix>,
+    pub biases: Vec<Matrix>,
+    /// Parameters of the inserted layers, in network order.
+    pub layers: Vec<Matrix>,
+}
+
+impl Network {
+    /// Every layer uses the same `activation`, the loss is mean squared error and the
+    /// optimizer plain SGD, see [`NetworkBuilder`] for anything else.
+    ///
+    /// With a `seed` both the initial weights and the shuffling in [`Network::fit`]
+    /// are reproducible.
+    pub fn new(
+        layers: Vec<usize>,
+        activation: Activation,
+        learning_rate: f64,
+        seed: Option<u64>,
+    ) -> Self {
+        let mut builder = NetworkBuilder::new(layers[0]).learning_rate(learning_rate);
+        for &size in &layers[1..] {
+            builder = builder.layer(size, activation);
+        }
+        if let Some(seed) = seed {
+            builder = builder.seed(seed);
+        }
+        builder.build()
+    }
+
+    pub fn builder(inputs: usize) -> NetworkBuilder {
+        NetworkBuilder::new(inputs)
+    }
+
+    pub fn layers(&self) -> &[usize] {
+        &self.layers
+    }
+
+    pub fn activations(&self) -> &[Activation] {
+        &self.activations
+    }
+
+    pub fn weights(&self) -> &[Matrix] {
+        &self.weights
+    }
+
+    pub fn biases(&self) -> &[Matrix] {
+        &self.biases
+    }
+
+    /// Dropout and batch norm layers following each dense layer.
+    pub fn inserted_layers(&self) -> &[Vec<Layer>] {
+        &self.inserted
+    }
+
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// Networks start in [`Mode::Eval`], for deterministic inference; [`Network::fit`]
+    /// trains in [`Mode::Train`] and restores the previous mode when done.
+    pub fn set_mode(&mut self, mode: Mode) {
+        self.mode = mode;
+    }
+
+    pub fn loss_fn(&self) -> Loss {
+        self.loss
+    }
+
+    pub fn set_optimizer(&mut self, optimizer: impl Optimizer + 'static) {
+        self.optimizer = Box::new(optimizer);
+    }
+
+    pub fn learning_rate(&self) -> f64 {
+        self.learning_rate
+    }
+
+    pub fn weight_decay(&self) -> f64 {
+        self.weight_decay
+    }
+
+    pub fn set_weight_decay(&mut self, weight_decay: f64) {
+        self.weight_decay = weight_decay;
+    }
+
+    pub fn schedule(&self) -> LearningRateSchedule {
+        self.schedule
+    }
+
+    pub fn set_schedule(&mut self, schedule: LearningRateSchedule) {
+        self.schedule = schedule;
+    }
+
+    /// Reseeds the RNG behind dropout and unseeded shuffling, like
+    /// [`NetworkBuilder::seed`] does for a new network.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    /// Rebuilds a network around already trained parameters, whose shapes the caller
+    /// has checked against `layers`.
+    ///
+    /// The optimizer is a fresh [`Sgd`], with no weight decay nor schedule, and the
+    /// RNG is unseeded until [`Network::set_seed`].
+    pub(crate) fn from_parts(
+        layers: Vec<usize>,
+        activations: Vec<Activation>,
+        loss: Loss,
+        learning_rate: f64,
+        weights: Vec<Matrix>,
+        biases: Vec<Matrix>,
+        inserted: Vec<Vec<Layer>>,
+    ) -> Network {
+        Network {
+            layers,
+            weights,
+            biases,
+            inserted,
+            data: vec![],
+            activated: vec![],
+            activations,
+            mode: Mode::Eval,
+            loss,
+            learning_rate,
+            optimizer: Box::new(Sgd::new()),
+            weight_decay: 0.0,
+            schedule: LearningRateSchedule::Constant,
+            epoch: 0,
+            rng: StdRng::from_os_rng(),
+        }
+    }
+
+    /// The network's own RNG, seeded by [`NetworkBuilder::seed`] or [`Network::set_seed`].
+    pub(crate) fn rng(&mut self) -> &mut StdRng {
+        &mut self.rng
+    }
+
+    /// Learning rate after applying the schedule for the current epoch.
+    pub fn current_learning_rate(&self) -> f64 {
+        self.schedule.learning_rate(self.learning_rate, self.epoch)
+    }
+
+    /// Sets the zero-based epoch the learning rate schedule is evaluated at.
+    pub fn set_epoch(&mut self, epoch: u32) {
+        self.epoch = epoch;
+    }
+
+    /// Loss of the network on `inputs` against `targets`.
+    pub fn loss(&mut self, inputs: Matrix, targets: &Matrix) -> f64 {
+        let outputs = self.feed_forward(inputs);
+        self.loss.loss(&outputs, targets)
+    }
+
+    pub fn feed_forward(&mut self, inputs: Matrix) -> Matrix {
+        self.try_feed_forward(inputs)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Like [`Network::feed_forward`], but fails instead of panicking when `inputs`
+    /// does not have one row per network input.
+    ///
+    /// A failed call leaves the state of the previous one untouched.
+    pub fn try_feed_forward(&mut self, inputs: Matrix) -> Result<Matrix, NetworkError> {
+        if inputs.rows != self.layers[0] {
+            return Err(NetworkError::InputSize {
+                expected: self.layers[0],
+                found: inputs.rows,
+            });
+        }
+
+        let last = self.layers.len() - 1;
+        // Layer outputs are computed in place, reusing the buffers of the last call.
+        self.data.resize_with(last + 1, Matrix::default);
+        self.data[0] = inputs;
+
+        self.activated.resize_with(last, Matrix::default);
+
+        for i in 0..last {
+            let (done, rest) = self.data.split_at_mut(i + 1);
+            let outputs = &mut rest[0];
+
+            // Shapes follow from `layers` once the inputs are checked.
+            self.weights[i].dot_multiply_into(&done[i], outputs);
+            outputs.add_column_in_place(&self.biases[i]);
+            self.activations[i].forward_in_place(outputs);
+
+            if !self.inserted[i].is_empty() {
+                self.activated[i] = std::mem::take(outputs);
+                let mut current = self.activated[i].clone();
+                for layer in &mut self.inserted[i] {
+                    current = layer.forward(&current, self.mode, &mut self.rng);
+                }
+                *outputs = current;
+            }
+        }
+
+        Ok(self.data[last].clone())
+    }
+
+    /// Gradients of the loss for the last [`Network::feed_forward`] call.
+    pub fn gradients(&self, targets: &Matrix) -> Gradients {
+        let last = self.layers.len() - 2;
+        let outputs = &self.data[last + 1];
+
+        let mut deltas = match self.activations[last] {
+            Activation::Softmax => self.loss.softmax_gradient(outputs, targets),
+            _ => None,
+        }
+        .unwrap_or_else(|| {
+            let output_gradients = self.loss.gradient(outputs, targets);
+            self.activations[last].backward(outputs, &output_gradients)
+        });
+
+        let mut weights = vec![];
+        let mut biases = vec![];
+        let mut layers = vec![];
+
+        for i in (0..=last).rev() {
+            weights.push(deltas.dot_multiply(&self.data[i].transpose()));
+            biases.push(deltas.sum_cols());
+
+            if i > 0 {
+                let mut errors = self.weights[i].transpose().dot_multiply(&deltas);
+                for layer in self.inserted[i - 1].iter().rev() {
+                    let (input_gradients, parameter_gradients) = layer.backward(&errors);
+                    errors = input_gradients;
+                    layers.extend(parameter_gradients.into_iter().rev());
+                }
+
+                let outputs = match self.inserted[i - 1].is_empty() {
+                    true => &self.data[i],
+                    false => &self.activated[i - 1],
+                };
+                deltas = self.activations[i - 1].backward(outputs, &errors);
+            }
+        }
+
+        weights.reverse();
+        biases.reverse();
+        layers.reverse();
+        Gradients {
+            weights,
+            biases,
+            layers,
+        }
+    }
+
+    /// Lets the optimizer take a step for the last [`Network::feed_forward`] call.
+    pub fn back_propogate(&mut self, targets: Matrix) {
+        let mut gradients = self.gradients(&targets);
+
+        // L2 penalty `weight_decay / 2 * |w|^2`, biases are left alone.
+        if self.weight_decay > 0.0 {
+            for (grad, weights) in gradients.weights.iter_mut().zip(&self.weights) {
+                *grad = grad.add(&weights.scale(self.weight_decay));
+            }
+        }
+
+        let learning_rate = self.current_learning_rate();
+        let grads: Vec<&Matrix> = gradients
+            .weights
+            .iter()
+            .chain(&gradients.biases)
+            .chain(&gradients.layers)
+            .collect();
+
+        let Network {
+            weights,
+            biases,
+            inserted,
+            optimizer,
+            ..
+        } = self;
+        optimizer.update(
+            &mut parameters_mut(weights, biases, inserted),
+            &grads,
+            learning_rate,
+        );
+    }
+
+    /// Per-sample training in input order, see [`Network::fit`] for mini-batches.
+    ///
+    /// Panics for networks with a batch norm layer, which needs larger batches.
+    pub fn train(&mut self, inputs: Vec<Vec<f64>>, targets: Vec<Vec<f64>>, epochs: u32) -> History {
+        let dataset = Dataset::from_samples(&inputs, &targets);
+        let config = TrainConfig {
+            epochs,
+            batch_size: 1,
+            shuffle: false,
+            ..Default::default()
+        };
+        self.fit(&dataset, None, &config, |_| {})
+    }
+}
+
+/// Weights, biases, then the parameters of the inserted layers: the order of
+/// [`Gradients`] and of the optimizer's state.
+fn parameters_mut<'a>(
+    weights: &'a mut [Matrix],
+    biases: &'a mut [Matrix],
+    inserted: &'a mut [Vec<Layer>],
+) -> Vec<&'a mut Matrix> {
+    weights
+        .iter_mut()
+        .chain(biases)
+        .chain(
+            inserted
+                .iter_mut()
+                .flatten()
+                .flat_map(Layer::parameters_mut),
+        )
+        .collect()
+}
+
+/// Defines a network layer by layer:
+///
+/// ```
+/// use video_14_NN::{Activation, Adam, Loss, Network};
+///
+/// let network = Network::builder(2)
+///     .layer(8, Activation::Relu)
+///     .layer(3, Activation::Softmax)
+///     .loss(Loss::CategoricalCrossEntropy)
+///     .optimizer(Adam::new())
+///     .learning_rate(0.01)
+///     .build();
+/// assert_eq!(network.layers(), &[2, 8, 3]);
+/// ```
+pub struct NetworkBuilder {
+    layers: Vec<usize>,
+    activations: Vec<Activation>,
+    loss: Loss,
+    learning_rate: f64,
+    initializers: Vec<Initializer>,
+    inserted: Vec<Vec<Layer>>,
+    optimizer: Box<dyn Optimizer>,
+    weight_decay: f64,
+    schedule: LearningRateSchedule,
+    seed: Option<u64>,
+}
+
+impl NetworkBuilder {
+    pub fn new(inputs: usize) -> Self {
+        NetworkBuilder {
+            layers: vec![inputs],
+            activations: vec![],
+            initializers: vec![],
+            inserted: vec![],
+            loss: Loss::MeanSquaredError,
+            learning_rate: 0.1,
+            optimizer: Box::new(Sgd::new()),
+            weight_decay: 0.0,
+            schedule: LearningRateSchedule::Constant,
+            seed: None,
+        }
+    }
+
+    /// Adds a dense layer of `size` neurons followed by `activation`, initialized with
+    /// [`Initializer::for_activation`].
+    pub fn layer(self, size: usize, activation: Activation) -> Self {
+        self.layer_with_init(size, activation, Initializer::for_activation(activation))
+    }
+
+    pub fn layer_with_init(
+        mut self,
+        size: usize,
+        activation: Activation,
+        initializer: Initializer,
+    ) -> Self {
+        self.layers.push(size);
+        self.activations.push(activation);
+        self.initializers.push(initializer);
+        self.inserted.push(vec![]);
+        self
+    }
+
+    /// Inserts `layer` after the most recently added dense layer.
+    pub fn insert(mut self, layer: Layer) -> Self {
+        self.inserted
+            .last_mut()
+            .expect("Add a dense layer before inserting layers")
+            .push(layer);
+        self
+    }
+
+    pub fn dropout(self, rate: f64) -> Self {
+        self.insert(Layer::Dropout(Dropout::new(rate)))
+    }
+
+    /// Batch normalization of the most recently added dense layer's outputs.
+    pub fn batch_norm(self) -> Self {
+        let features = *self.layers.last().unwrap();
+        self.insert(Layer::BatchNorm(BatchNorm::new(features)))
+    }
+
+    pub fn loss(mut self, loss: Loss) -> Self {
+        self.loss = loss;
+        self
+    }
+
+    pub fn learning_rate(mut self, learning_rate: f64) -> Self {
+        self.learning_rate = learning_rate;
+        self
+    }
+
+    pub fn optimizer(mut self, optimizer: impl Optimizer + 'static) -> Self {
+        self.optimizer = Box::new(optimizer);
+        self
+    }
+
+    /// L2 regularization strength applied to the weights.
+    pub fn weight_decay(mut self, weight_decay: f64) -> Self {
+        self.weight_decay = weight_decay;
+        self
+    }
+
+    pub fn schedule(mut self, schedule: LearningRateSchedule) -> Self {
+        self.schedule = schedule;
+        self
+    }
+
+    /// Seeds the weight initialization and the network's RNG, used by
+    /// [`Network::fit`] unless [`TrainConfig::seed`] is set.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn build(self) -> Network {
+        self.try_build().unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Like [`NetworkBuilder::build`], but fails instead of panicking on an empty or
+    /// zero-sized layer, misplaced inserted layers or an initializer that cannot draw
+    /// its layer's weights.
+    pub fn try_build(self) -> Result<Network, NetworkError> {
+        if self.activations.is_empty() {
+            return Err(NetworkError::Invalid(
+                "a network needs at least one layer".to_string(),
+            ));
+        }
+        if self.layers.contains(&0) {
+            return Err(NetworkError::Invalid(format!(
+                "layer sizes {:?}",
+                self.layers
+            )));
+        }
+        if !self.inserted.last().unwrap().is_empty() {
+            return Err(NetworkError::Invalid(
+                "inserted layers must be followed by a dense layer".to_string(),
+            ));
+        }
+        for (inserted, &size) in self.inserted.iter().zip(&self.layers[1..]) {
+            for layer in inserted {
+                if let Layer::BatchNorm(batch_norm) = layer
+                    && batch_norm.features() != size
+                {
+                    return Err(NetworkError::Invalid(format!(
+                        "batch norm of {} features after a layer of {size}",
+                        batch_norm.features()
+                    )));
+                }
+            }
+        }
+
+        let mut rng = match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_os_rng(),
+        };
+        let mut weights = vec![];
+        let mut biases = vec![];
+
+        for (i, initializer) in self.initializers.iter().enumerate() {
+            weights.push(initializer.try_weights(self.layers[i], self.layers[i + 1], &mut rng)?);
+            biases.push(Matrix::from(vec![0.0; self.layers[i + 1]]));
+        }
+
+        Ok(Network {
+            layers: self.layers,
+            weights,
+            biases,
+            inserted: self.inserted,
+            data: vec![],
+            activated: vec![],
+            activations: self.activations,
+            mode: Mode::Eval,
+            loss: self.loss,
+            learning_rate: self.learning_rate,
+            optimizer: self.optimizer,
+            weight_decay: self.weight_decay,
+            schedule: self.schedule,
+            epoch: 0,
+            rng,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Value `k` of parameter `p`, in [`Gradients`] order.
+    fn parameter(network: &Network, p: usize, k: usize) -> f64 {
+        let mut parameters = network.weights.iter().chain(&network.biases).chain(
+            network
+                .inserted
+                .iter()
+                .flatten()
+                .flat_map(Layer::parameters),
+        );
+        parameters.nth(p).unwrap().data[k]
+    }
+
+    fn set_parameter(network: &mut Network, p: usize, k: usize, value: f64) {
+        let Network {
+            weights,
+            biases,
+            inserted,
+            ..
+        } = network;
+        parameters_mut(weights, biases, inserted)[p].data[k] = value;
+    }
+
+    fn check_gradients(mut network: Network, inputs: Matrix, targets: Matrix) {
+        let eps = 1e-6;
+        // Dropout has to draw the same mask for every evaluation.
+        let rng = network.rng.clone();
+        let loss = |network: &mut Network| {
+            network.rng = rng.clone();
+            network.loss(inputs.clone(), &targets)
+        };
+
+        loss(&mut network);
+        let analytic = network.gradients(&targets);
+        let analytic: Vec<Matrix> = analytic
+            .weights
+            .into_iter()
+            .chain(analytic.biases)
+            .chain(analytic.layers)
+            .collect();
+
+        for (p, analytic) in analytic.iter().enumerate() {
+            for k in 0..analytic.data.len() {
+                let original = parameter(&network, p, k);
+
+                set_parameter(&mut network, p, k, original + eps);
+                let plus = loss(&mut network);
+                set_parameter(&mut network, p, k, original - eps);
+                let minus = loss(&mut network);
+                set_parameter(&mut network, p, k, original);
+
+                let numeric = (plus - minus) / (2.0 * eps);
+                let analytic = analytic.data[k];
+                assert!(
+                    (numeric - analytic).abs() < 1e-6 * (1.0 + numeric.abs()),
+                    "parameter {p}/{k}: numeric {numeric} analytic {analytic}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn gradient_check_mse() {
+        let network = Network::builder(3)
+            .layer(4, Activation::Tanh)
+            .layer(2, Activation::Identity)
+            .loss(Loss::MeanSquaredError)
+            .seed(0)
+            .build();
+
+        check_gradients(
+            network,
+            Matrix::from(vec![0.5, -0.2, 0.9]),
+            Matrix::from(vec![1.0, -1.0]),
+        );
+    }
+
+    #[test]
+    fn gradient_check_binary_cross_entropy() {
+        let network = Network::builder(2)
+            .layer(3, Activation::LeakyRelu(0.1))
+            .layer(1, Activation::Sigmoid)
+            .loss(Loss::BinaryCrossEntropy)
+            .seed(0)
+            .build();
+
+        check_gradients(
+            network,
+            Matrix::from(vec![0.3, 0.8]),
+            Matrix::from(vec![1.0]),
+        );
+    }
+
+    #[test]
+    fn gradient_check_softmax_cross_entropy() {
+        let network = Network::builder(3)
+            .layer(5, Activation::Sigmoid)
+            .layer(4, Activation::Softmax)
+            .loss(Loss::CategoricalCrossEntropy)
+            .seed(0)
+            .build();
+
+        check_gradients(
+            network,
+            Matrix::from(vec![0.1, -0.4, 0.7]),
+            Matrix::from(vec![0.0, 0.0, 1.0, 0.0]),
+        );
+    }
+
+    #[test]
+    fn gradient_check_softmax_mse() {
+        let network = Network::builder(2)
+            .layer(3, Activation::Softmax)
+            .loss(Loss::MeanSquaredError)
+            .seed(0)
+            .build();
+
+        check_gradients(
+            network,
+            Matrix::from(vec![0.6, -0.3]),
+            Matrix::from(vec![0.0, 1.0, 0.0]),
+        );
+    }
+
+    #[test]
+    fn gradient_check_dropout_and_batch_norm() {
+        let mut network = Network::builder(3)
+            .layer(5, Activation::Tanh)
+            .batch_norm()
+            .dropout(0.3)
+            .layer(4, Activation::Sigmoid)
+            .batch_norm()
+            .layer(2, Activation::Softmax)
+            .loss(Loss::CategoricalCrossEntropy)
+            .seed(0)
+            .build();
+        network.set_mode(Mode::Train);
+
+        check_gradients(
+            network,
+            Matrix::from_columns(&[
+                vec![0.5, -0.2, 0.9],
+                vec![-1.0, 0.3, 0.1],
+                vec![0.2, 0.8, -0.6],
+                vec![0.0, -0.5, 0.4],
+            ]),
+            Matrix::from_columns(&[
+                vec![1.0, 0.0],
+                vec![0.0, 1.0],
+                vec![0.0, 1.0],
+                vec![1.0, 0.0],
+            ]),
+        );
+    }
+
+    #[test]
+    fn gradient_check_batch_norm_eval_mode() {
+        let inputs = Matrix::from_columns(&[vec![0.5, -0.2], vec![-1.0, 0.3], vec![0.2, 0.8]]);
+        let targets = Matrix::from_columns(&[vec![1.0], vec![-1.0], vec![0.5]]);
+        let mut network = Network::builder(2)
+            .layer(3, Activation::Relu)
+            .batch_norm()
+            .dropout(0.5)
+            .layer(1, Activation::Identity)
+            .seed(1)
+            .build();
+        network.set_mode(Mode::Train);
+        for _ in 0..5 {
+            network.feed_forward(inputs.clone());
+        }
+        network.set_mode(Mode::Eval);
+
+        check_gradients(network, inputs, targets);
+    }
+
+    #[test]
+    fn eval_mode_is_deterministic() {
+        let mut network = Network::builder(4)
+            .layer(16, Activation::Relu)
+            .dropout(0.5)
+            .layer(2, Activation::Identity)
+            .seed(3)
+            .build();
+        let inputs = Matrix::from(vec![0.1, 0.2, 0.3, 0.4]);
+        assert_eq!(network.mode(), Mode::Eval);
+
+        network.set_mode(Mode::Train);
+        let first = network.feed_forward(inputs.clone());
+        let second = network.feed_forward(inputs.clone());
+        assert_ne!(first.data, second.data);
+
+        network.set_mode(Mode::Eval);
+        let first = network.feed_forward(inputs.clone());
+        let second = network.feed_forward(inputs);
+        assert_eq!(first.data, second.data);
+    }
+
+    #[test]
+    fn fit_restores_mode() {
+        let mut network = Network::builder(2)
+            .layer(4, Activation::Tanh)
+            .dropout(0.2)
+            .layer(1, Activation::Sigmoid)
+            .seed(0)
+            .build();
+        network.set_mode(Mode::Train);
+
+        network.train(
+            vec![vec![0.0, 1.0], vec![1.0, 0.0]],
+            vec![vec![1.0], vec![0.0]],
+            3,
+        );
+
+        assert_eq!(network.mode(), Mode::Train);
+    }
+
+    #[test]
+    #[should_panic(expected = "Batch norm needs batches of at least 2 samples")]
+    fn batch_norm_rejects_single_sample_batches() {
+        let mut network = Network::builder(2)
+            .layer(3, Activation::Relu)
+            .batch_norm()
+            .layer(1, Activation::Sigmoid)
+            .seed(0)
+            .build();
+
+        network.train(
+            vec![vec![0.0, 1.0], vec![1.0, 0.0]],
+            vec![vec![1.0], vec![0.0]],
+            1,
+        );
+    }
+
+    #[test]
+    fn learning_rate_scales_the_step() {
+        let inputs = Matrix::from(vec![0.5, -0.5]);
+        let targets = Matrix::from(vec![1.0]);
+        let mut network = Network::new(vec![2, 1], Activation::Sigmoid, 0.25, Some(1));
+        let weights_before = network.weights[0].clone();
+
+        network.feed_forward(inputs);
+        let gradients = network.gradients(&targets);
+        network.back_propogate(targets);
+
+        for k in 0..weights_before.data.len() {
+            let step = weights_before.data[k] - network.weights[0].data[k];
+            assert!((step - 0.25 * gradients.weights[0].data[k]).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn weight_decay_shrinks_weights() {
+        let mut network = Network::builder(2)
+            .layer(1, Activation::Identity)
+            .learning_rate(0.1)
+            .weight_decay(0.5)
+            .seed(0)
+            .build();
+        let weights_before = network.weights[0].clone();
+        let biases_before = network.biases[0].clone();
+
+        // Outputs already match the targets, so only the decay moves the weights.
+        let outputs = network.feed_forward(Matrix::from(vec![0.0, 0.0]));
+        network.back_propogate(outputs);
+
+        for (after, before) in network.weights[0].data.iter().zip(&weights_before.data) {
+            assert!((after - before * 0.95).abs() < 1e-12);
+        }
+        assert_eq!(network.biases[0].data, biases_before.data);
+    }
+
+    #[test]
+    fn wrong_input_size_is_an_error() {
+        let mut network = Network::new(vec![3, 2], Activation::Sigmoid, 0.1, Some(0));
+        let inputs = Matrix::from(vec![1.0, 2.0, 3.0]);
+        let targets = Matrix::from(vec![1.0, 0.0]);
+        network.feed_forward(inputs.clone());
+        let gradients = network.gradients(&targets);
+
+        let err = network
+            .try_feed_forward(Matrix::from(vec![1.0, 2.0]))
+            .unwrap_err();
+
+        assert_eq!(
+            err,
+            NetworkError::InputSize {
+                expected: 3,
+                found: 2
+            }
+        );
+        // The cached forward pass still backs the previous call.
+        assert_eq!(
+            network.gradients(&targets).weights[0].data,
+            gradients.weights[0].data
+        );
+    }
+
+    #[test]
+    fn invalid_builder_settings_are_errors() {
+        let empty_range = Network::builder(2)
+            .layer_with_init(
+                3,
+                Activation::Tanh,
+                Initializer::Uniform {
+                    low: 0.5,
+                    high: -0.5,
+                },
+            )
+            .try_build();
+        let no_inputs = Network::builder(0).layer(3, Activation::Relu).try_build();
+
+        assert!(matches!(empty_range, Err(NetworkError::Invalid(_))));
+        assert!(matches!(no_inputs, Err(NetworkError::Invalid(_))));
+    }
+
+    #[test]
+    fn seeded_networks_are_reproducible() {
+        let build = |seed| {
+            Network::builder(2)
+                .layer(4, Activation::Relu)
+                .layer_with_init(1, Activation::Sigmoid, Initializer::XavierNormal)
+                .seed(seed)
+                .build()
+        };
+        let dataset = Dataset::from_samples(
+            &[vec![0.0, 1.0], vec![1.0, 0.0], vec![1.0, 1.0]],
+            &[vec![1.0], vec![1.0], vec![0.0]],
+        );
+        let config = TrainConfig {
+            epochs: 5,
+            batch_size: 2,
+            ..Default::default()
+        };
+
+        let (mut a, mut b) = (build(7), build(7));
+        assert_eq!(a.weights[0].data, b.weights[0].data);
+        assert_ne!(a.weights[0].data, build(8).weights[0].data);
+        assert!(a.biases.iter().all(|b| b.data.iter().all(|&v| v == 0.0)));
+
+        let a = a.fit(&dataset, None, &config, |_| {});
+        let b = b.fit(&dataset, None, &config, |_| {});
+        assert_eq!(a.epochs, b.epochs);
+    }
+
+    #[test]
+    fn adam_trains_xor() {
+        let inputs = vec![
+            vec![0.0, 0.0],
+            vec![0.0, 1.0],
+            vec![1.0, 0.0],
+            vec![1.0, 1.0],
+        ];
+        let targets = vec![vec![0.0], vec![1.0], vec![1.0], vec![0.0]];
+        let dataset = Dataset::from_samples(&inputs, &targets);
+        let mut network = Network::builder(2)
+            .layer(8, Activation::Tanh)
+            .layer(1, Activation::Sigmoid)
+            .loss(Loss::BinaryCrossEntropy)
+            .optimizer(crate::Adam::new())
+            .learning_rate(0.05)
+            .schedule(LearningRateSchedule::Cosine {
+                epochs: 500,
+                min_rate: 0.005,
+            })
+            .seed(0)
+            .build();
+        let config = TrainConfig {
+            epochs: 500,
+            batch_size: 4,
+            seed: Some(0),
+            ..Default::default()
+        };
+
+        let history = network.fit(&dataset, None, &config, |_| {});
+
+        let first = &history.epochs[0];
+        let last = history.last().unwrap();
+        assert!(last.loss < first.loss);
+        assert!(last.learning_rate < first.learning_rate);
+    }
+
+    #[test]
+    fn loss_decreases_on_xor() {
+        let inputs = vec![
+            vec![0.0, 0.0],
+            vec![0.0, 1.0],
+            vec![1.0, 0.0],
+            vec![1.0, 1.0],
+        ];
+        let targets = vec![vec![0.0], vec![1.0], vec![1.0], vec![0.0]];
+        let mut network = Network::builder(2)
+            .layer(4, Activation::Tanh)
+            .layer(1, Activation::Sigmoid)
+            .loss(Loss::BinaryCrossEntropy)
+            .learning_rate(0.5)
+            .seed(0)
+            .build();
+
+        let total_loss = |network: &mut Network| -> f64 {
+            inputs
+                .iter()
+                .zip(&targets)
+                .map(|(x, t)| network.loss(Matrix::from(x.clone()), &Matrix::from(t.clone())))
+                .sum()
+        };
+
+        let before = total_loss(&mut network);
+        network.train(inputs.clone(), targets.clone(), 2000);
+        let after = total_loss(&mut network);
+
+        assert!(after < before, "XOR loss went from {before} to {after}");
+    }
+}
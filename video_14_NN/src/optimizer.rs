@@ -0,0 +1,295 @@
+use std::f64::consts::PI;
+
+use crate::Matrix;
+
+/// Updates parameters from their gradients.
+///
+/// `params` and `grads` always come in the same order, so implementations keep their
+/// per-parameter state by index. The network passes every layer's weights, then
+/// every layer's biases, then the scale and shift of each batch norm layer in network
+/// order; nothing else should be assumed about the layout.
+pub trait Optimizer {
+    fn update(&mut self, params: &mut [&mut Matrix], grads: &[&Matrix], learning_rate: f64);
+}
+
+/// Zeroed state shaped like `params`, kept as is while the shapes still match.
+fn init_state(state: &mut Vec<Matrix>, params: &[&mut Matrix]) {
+    let matches = state.len() == params.len()
+        && state
+            .iter()
+            .zip(params)
+            .all(|(s, p)| s.rows == p.rows && s.cols == p.cols);
+
+    if !matches {
+        *state = params
+            .iter()
+            .map(|p| Matrix {
+                rows: p.rows,
+                cols: p.cols,
+                data: vec![0.0; p.data.len()],
+            })
+            .collect();
+    }
+}
+
+/// Stochastic gradient descent, with optional (heavy-ball) momentum.
+#[derive(Debug, Clone, Default)]
+pub struct Sgd {
+    pub momentum: f64,
+    velocities: Vec<Matrix>,
+}
+
+impl Sgd {
+    pub fn new() -> Self {
+        Sgd::default()
+    }
+
+    pub fn with_momentum(momentum: f64) -> Self {
+        Sgd {
+            momentum,
+            velocities: vec![],
+        }
+    }
+}
+
+impl Optimizer for Sgd {
+    fn update(&mut self, params: &mut [&mut Matrix], grads: &[&Matrix], learning_rate: f64) {
+        if self.momentum == 0.0 {
+            for (param, grad) in params.iter_mut().zip(grads) {
+                for (p, g) in param.data.iter_mut().zip(&grad.data) {
+                    *p -= learning_rate * g;
+                }
+            }
+            return;
+        }
+
+        init_state(&mut self.velocities, params);
+        for ((param, grad), velocity) in params.iter_mut().zip(grads).zip(&mut self.velocities) {
+            for ((p, g), v) in param
+                .data
+                .iter_mut()
+                .zip(&grad.data)
+                .zip(&mut velocity.data)
+            {
+                *v = self.momentum * *v - learning_rate * g;
+                *p += *v;
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RmsProp {
+    pub decay: f64,
+    pub epsilon: f64,
+    cache: Vec<Matrix>,
+}
+
+impl RmsProp {
+    pub fn new() -> Self {
+        RmsProp::default()
+    }
+
+    pub fn with_decay(decay: f64) -> Self {
+        RmsProp {
+            decay,
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for RmsProp {
+    fn default() -> Self {
+        RmsProp {
+            decay: 0.9,
+            epsilon: 1e-8,
+            cache: vec![],
+        }
+    }
+}
+
+impl Optimizer for RmsProp {
+    fn update(&mut self, params: &mut [&mut Matrix], grads: &[&Matrix], learning_rate: f64) {
+        init_state(&mut self.cache, params);
+        for ((param, grad), cache) in params.iter_mut().zip(grads).zip(&mut self.cache) {
+            for ((p, g), c) in param.data.iter_mut().zip(&grad.data).zip(&mut cache.data) {
+                *c = self.decay * *c + (1.0 - self.decay) * g * g;
+                *p -= learning_rate * g / (c.sqrt() + self.epsilon);
+            }
+        }
+    }
+}
+
+/// Adam with bias-corrected first and second moment estimates.
+#[derive(Debug, Clone)]
+pub struct Adam {
+    pub beta1: f64,
+    pub beta2: f64,
+    pub epsilon: f64,
+    step: i32,
+    moments: Vec<Matrix>,
+    velocities: Vec<Matrix>,
+}
+
+impl Adam {
+    pub fn new() -> Self {
+        Adam::default()
+    }
+
+    pub fn with_betas(beta1: f64, beta2: f64) -> Self {
+        Adam {
+            beta1,
+            beta2,
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for Adam {
+    fn default() -> Self {
+        Adam {
+            beta1: 0.9,
+            beta2: 0.999,
+            epsilon: 1e-8,
+            step: 0,
+            moments: vec![],
+            velocities: vec![],
+        }
+    }
+}
+
+impl Optimizer for Adam {
+    fn update(&mut self, params: &mut [&mut Matrix], grads: &[&Matrix], learning_rate: f64) {
+        init_state(&mut self.moments, params);
+        init_state(&mut self.velocities, params);
+        self.step += 1;
+
+        let m_correction = 1.0 - self.beta1.powi(self.step);
+        let v_correction = 1.0 - self.beta2.powi(self.step);
+
+        for (i, (param, grad)) in params.iter_mut().zip(grads).enumerate() {
+            let moments = &mut self.moments[i].data;
+            let velocities = &mut self.velocities[i].data;
+
+            for (k, (p, g)) in param.data.iter_mut().zip(&grad.data).enumerate() {
+                moments[k] = self.beta1 * moments[k] + (1.0 - self.beta1) * g;
+                velocities[k] = self.beta2 * velocities[k] + (1.0 - self.beta2) * g * g;
+
+                let m_hat = moments[k] / m_correction;
+                let v_hat = velocities[k] / v_correction;
+                *p -= learning_rate * m_hat / (v_hat.sqrt() + self.epsilon);
+            }
+        }
+    }
+}
+
+/// Learning rate as a function of the (zero-based) epoch.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum LearningRateSchedule {
+    #[default]
+    Constant,
+    /// Multiplies the rate by `gamma` every `step_size` epochs.
+    Step { step_size: u32, gamma: f64 },
+    /// Multiplies the rate by `gamma` every epoch.
+    Exponential { gamma: f64 },
+    /// Cosine annealing from the base rate down to `min_rate` over `epochs` epochs.
+    Cosine { epochs: u32, min_rate: f64 },
+}
+
+impl LearningRateSchedule {
+    pub fn learning_rate(&self, base_rate: f64, epoch: u32) -> f64 {
+        match *self {
+            LearningRateSchedule::Constant => base_rate,
+            LearningRateSchedule::Step { step_size, gamma } => {
+                base_rate * gamma.powi((epoch / step_size.max(1)) as i32)
+            }
+            LearningRateSchedule::Exponential { gamma } => base_rate * gamma.powi(epoch as i32),
+            LearningRateSchedule::Cosine { epochs, min_rate } => {
+                let progress = epoch.min(epochs) as f64 / epochs.max(1) as f64;
+                min_rate + 0.5 * (base_rate - min_rate) * (1.0 + (PI * progress).cos())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Minimizes `sum((x - 3)^2)` from zero and returns the final point.
+    fn minimize(optimizer: &mut dyn Optimizer, learning_rate: f64, steps: usize) -> Matrix {
+        let mut x = Matrix::from(vec![0.0, 0.0]);
+        for _ in 0..steps {
+            let grad = Matrix {
+                rows: 2,
+                cols: 1,
+                data: x.data.iter().map(|v| 2.0 * (v - 3.0)).collect(),
+            };
+            optimizer.update(&mut [&mut x], &[&grad], learning_rate);
+        }
+        x
+    }
+
+    #[test]
+    fn optimizers_converge_on_quadratic() {
+        let optimizers: Vec<(Box<dyn Optimizer>, f64)> = vec![
+            (Box::new(Sgd::new()), 0.1),
+            (Box::new(Sgd::with_momentum(0.9)), 0.01),
+            (Box::new(RmsProp::new()), 0.01),
+            (Box::new(Adam::new()), 0.1),
+        ];
+
+        for (mut optimizer, learning_rate) in optimizers {
+            let x = minimize(optimizer.as_mut(), learning_rate, 2000);
+            for v in &x.data {
+                assert!((v - 3.0).abs() < 1e-2, "ended at {v}");
+            }
+        }
+    }
+
+    #[test]
+    fn adam_first_step_is_learning_rate() {
+        let mut x = Matrix::from(vec![0.0]);
+        let grad = Matrix::from(vec![-123.0]);
+
+        Adam::new().update(&mut [&mut x], &[&grad], 0.01);
+
+        assert!((x.data[0] - 0.01).abs() < 1e-9);
+    }
+
+    #[test]
+    fn momentum_accumulates() {
+        let mut x = Matrix::from(vec![0.0]);
+        let grad = Matrix::from(vec![1.0]);
+        let mut sgd = Sgd::with_momentum(0.5);
+
+        sgd.update(&mut [&mut x], &[&grad], 1.0);
+        sgd.update(&mut [&mut x], &[&grad], 1.0);
+
+        // -1, then -1 + 0.5 * -1
+        assert!((x.data[0] + 2.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn schedules() {
+        let step = LearningRateSchedule::Step {
+            step_size: 10,
+            gamma: 0.5,
+        };
+        assert_eq!(step.learning_rate(1.0, 9), 1.0);
+        assert_eq!(step.learning_rate(1.0, 10), 0.5);
+        assert_eq!(step.learning_rate(1.0, 25), 0.25);
+
+        let exponential = LearningRateSchedule::Exponential { gamma: 0.9 };
+        assert!((exponential.learning_rate(1.0, 2) - 0.81).abs() < 1e-12);
+
+        let cosine = LearningRateSchedule::Cosine {
+            epochs: 100,
+            min_rate: 0.1,
+        };
+        assert!((cosine.learning_rate(1.0, 0) - 1.0).abs() < 1e-12);
+        assert!((cosine.learning_rate(1.0, 50) - 0.55).abs() < 1e-12);
+        assert!((cosine.learning_rate(1.0, 100) - 0.1).abs() < 1e-12);
+        assert!((cosine.learning_rate(1.0, 500) - 0.1).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,881 @@
+use std::{fmt, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Activation, BatchNorm, Dropout, Layer, LearningRateSchedule, Loss, Matrix, Network};
+
+/// Version written by this build; files from any other version are rejected.
+pub const FORMAT_VERSION: u32 = 1;
+
+const JSON_FORMAT_NAME: &str = "video_14_NN";
+const BINARY_MAGIC: &[u8; 4] = b"VNNB";
+
+/// Binary tags are the indices in these tables; the leaky ReLU alpha is stored apart.
+const ACTIVATIONS: [Activation; 6] = [
+    Activation::Identity,
+    Activation::Sigmoid,
+    Activation::Tanh,
+    Activation::Relu,
+    Activation::LeakyRelu(0.0),
+    Activation::Softmax,
+];
+const LOSSES: [Loss; 3] = [
+    Loss::MeanSquaredError,
+    Loss::BinaryCrossEntropy,
+    Loss::CategoricalCrossEntropy,
+];
+
+#[derive(Debug)]
+pub enum ModelError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    /// Not a saved network at all (wrong magic or format name).
+    UnknownFormat,
+    UnsupportedVersion {
+        found: u32,
+        expected: u32,
+    },
+    ShapeMismatch {
+        what: String,
+        expected: (usize, usize),
+        found: (usize, usize),
+    },
+    UnknownActivation(String),
+    UnknownLoss(String),
+    Invalid(String),
+    /// The binary data ended early.
+    Truncated,
+}
+
+impl fmt::Display for ModelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModelError::Io(err) => write!(f, "io error: {err}"),
+            ModelError::Json(err) => write!(f, "json error: {err}"),
+            ModelError::UnknownFormat => write!(f, "not a saved network"),
+            ModelError::UnsupportedVersion { found, expected } => {
+                write!(f, "unsupported format version {found}, expected {expected}")
+            }
+            ModelError::ShapeMismatch {
+                what,
+                expected,
+                found,
+            } => write!(
+                f,
+                "{what} has shape {}x{}, expected {}x{}",
+                found.0, found.1, expected.0, expected.1
+            ),
+            ModelError::UnknownActivation(name) => write!(f, "unknown activation '{name}'"),
+            ModelError::UnknownLoss(name) => write!(f, "unknown loss '{name}'"),
+            ModelError::Invalid(msg) => write!(f, "invalid network: {msg}"),
+            ModelError::Truncated => write!(f, "unexpected end of data"),
+        }
+    }
+}
+
+impl std::error::Error for ModelError {}
+
+impl From<std::io::Error> for ModelError {
+    fn from(err: std::io::Error) -> Self {
+        ModelError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for ModelError {
+    fn from(err: serde_json::Error) -> Self {
+        ModelError::Json(err)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ModelFormat {
+    Json,
+    Binary,
+}
+
+#[derive(Serialize, Deserialize)]
+struct NetworkFile {
+    format: String,
+    version: u32,
+    layers: Vec<usize>,
+    activations: Vec<ActivationSpec>,
+    loss: String,
+    learning_rate: f64,
+    weight_decay: f64,
+    schedule: ScheduleSpec,
+    weights: Vec<MatrixSpec>,
+    biases: Vec<MatrixSpec>,
+    inserted: Vec<Vec<LayerSpec>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ActivationSpec {
+    name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    alpha: Option<f64>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum LayerSpec {
+    Dropout {
+        rate: f64,
+    },
+    BatchNorm {
+        momentum: f64,
+        epsilon: f64,
+        scale: Vec<f64>,
+        shift: Vec<f64>,
+        running_mean: Vec<f64>,
+        running_variance: Vec<f64>,
+    },
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ScheduleSpec {
+    Constant,
+    Step { step_size: u32, gamma: f64 },
+    Exponential { gamma: f64 },
+    Cosine { epochs: u32, min_rate: f64 },
+}
+
+#[derive(Serialize, Deserialize)]
+struct MatrixSpec {
+    rows: usize,
+    cols: usize,
+    data: Vec<f64>,
+}
+
+impl Network {
+    pub fn to_json(&self) -> Result<String, ModelError> {
+        let file = NetworkFile {
+            format: JSON_FORMAT_NAME.to_string(),
+            version: FORMAT_VERSION,
+            layers: self.layers().to_vec(),
+            activations: self
+                .activations()
+                .iter()
+                .map(|activation| ActivationSpec {
+                    name: activation.name().to_string(),
+                    alpha: match activation {
+                        Activation::LeakyRelu(alpha) => Some(*alpha),
+                        _ => None,
+                    },
+                })
+                .collect(),
+            loss: self.loss_fn().name().to_string(),
+            learning_rate: self.learning_rate(),
+            weight_decay: self.weight_decay(),
+            schedule: ScheduleSpec::from(self.schedule()),
+            weights: self.weights().iter().map(MatrixSpec::from).collect(),
+            biases: self.biases().iter().map(MatrixSpec::from).collect(),
+            inserted: self
+                .inserted_layers()
+                .iter()
+                .map(|layers| layers.iter().map(LayerSpec::from).collect())
+                .collect(),
+        };
+
+        Ok(serde_json::to_string_pretty(&file)?)
+    }
+
+    pub fn from_json(json: &str) -> Result<Network, ModelError> {
+        let file: NetworkFile = serde_json::from_str(json)?;
+
+        if file.format != JSON_FORMAT_NAME {
+            return Err(ModelError::UnknownFormat);
+        }
+        check_version(file.version)?;
+
+        let activations = file
+            .activations
+            .iter()
+            .map(|spec| {
+                Activation::from_name(&spec.name, spec.alpha)
+                    .ok_or_else(|| ModelError::UnknownActivation(spec.name.clone()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let loss = Loss::from_name(&file.loss).ok_or(ModelError::UnknownLoss(file.loss))?;
+
+        let weights = file
+            .weights
+            .into_iter()
+            .map(Matrix::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        let biases = file
+            .biases
+            .into_iter()
+            .map(Matrix::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut network = build(
+            file.layers,
+            activations,
+            loss,
+            file.learning_rate,
+            weights,
+            biases,
+            file.inserted,
+        )?;
+        network.set_weight_decay(file.weight_decay);
+        network.set_schedule(file.schedule.into());
+        Ok(network)
+    }
+
+    /// Little-endian binary encoding: magic, version, layer sizes, activation and
+    /// loss tags, learning rate, weight decay, schedule, then every layer's weights
+    /// followed by its biases and its inserted layers.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(BINARY_MAGIC);
+        bytes.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+
+        bytes.extend_from_slice(&(self.layers().len() as u32).to_le_bytes());
+        for &size in self.layers() {
+            bytes.extend_from_slice(&(size as u32).to_le_bytes());
+        }
+
+        for activation in self.activations() {
+            bytes.push(tag(&ACTIVATIONS, |a| a.name() == activation.name()));
+            if let Activation::LeakyRelu(alpha) = activation {
+                bytes.extend_from_slice(&alpha.to_le_bytes());
+            }
+        }
+        bytes.push(tag(&LOSSES, |loss| *loss == self.loss_fn()));
+        bytes.extend_from_slice(&self.learning_rate().to_le_bytes());
+        bytes.extend_from_slice(&self.weight_decay().to_le_bytes());
+
+        let (schedule_tag, epochs, values) = match self.schedule() {
+            LearningRateSchedule::Constant => (0, None, vec![]),
+            LearningRateSchedule::Step { step_size, gamma } => (1, Some(step_size), vec![gamma]),
+            LearningRateSchedule::Exponential { gamma } => (2, None, vec![gamma]),
+            LearningRateSchedule::Cosine { epochs, min_rate } => (3, Some(epochs), vec![min_rate]),
+        };
+        bytes.push(schedule_tag);
+        if let Some(epochs) = epochs {
+            bytes.extend_from_slice(&epochs.to_le_bytes());
+        }
+        for value in values {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+
+        for ((weights, biases), inserted) in self
+            .weights()
+            .iter()
+            .zip(self.biases())
+            .zip(self.inserted_layers())
+        {
+            for value in weights.data.iter().chain(&biases.data) {
+                bytes.extend_from_slice(&value.to_le_bytes());
+            }
+
+            bytes.extend_from_slice(&(inserted.len() as u32).to_le_bytes());
+            for layer in inserted {
+                let values = match LayerSpec::from(layer) {
+                    LayerSpec::Dropout { rate } => {
+                        bytes.push(0);
+                        vec![rate]
+                    }
+                    LayerSpec::BatchNorm {
+                        momentum,
+                        epsilon,
+                        scale,
+                        shift,
+                        running_mean,
+                        running_variance,
+                    } => {
+                        bytes.push(1);
+                        [
+                            vec![momentum, epsilon],
+                            scale,
+                            shift,
+                            running_mean,
+                            running_variance,
+                        ]
+                        .concat()
+                    }
+                };
+                for value in values {
+                    bytes.extend_from_slice(&value.to_le_bytes());
+                }
+            }
+        }
+
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Network, ModelError> {
+        let mut reader = Reader { bytes };
+
+        if reader.take(4)? != BINARY_MAGIC {
+            return Err(ModelError::UnknownFormat);
+        }
+        check_version(reader.u32()?)?;
+
+        let layer_count = reader.u32()? as usize;
+        if layer_count < 2 {
+            return Err(ModelError::Invalid(format!("{layer_count} layers")));
+        }
+        let layers = (0..layer_count)
+            .map(|_| reader.u32().map(|size| size as usize))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut activations = Vec::with_capacity(layer_count - 1);
+        for _ in 1..layer_count {
+            let tag = reader.u8()?;
+            activations.push(match ACTIVATIONS.get(tag as usize) {
+                Some(Activation::LeakyRelu(_)) => Activation::LeakyRelu(reader.f64()?),
+                Some(&activation) => activation,
+                None => return Err(ModelError::UnknownActivation(format!("tag {tag}"))),
+            });
+        }
+
+        let tag = reader.u8()?;
+        let loss = *LOSSES
+            .get(tag as usize)
+            .ok_or_else(|| ModelError::UnknownLoss(format!("tag {tag}")))?;
+        let learning_rate = reader.f64()?;
+
+        let weight_decay = reader.f64()?;
+        let schedule = reader.schedule()?;
+
+        let mut weights = Vec::with_capacity(layer_count - 1);
+        let mut biases = Vec::with_capacity(layer_count - 1);
+        let mut inserted = vec![];
+        for i in 0..layer_count - 1 {
+            let (rows, cols) = (layers[i + 1], layers[i]);
+            weights.push(reader.matrix(rows, cols)?);
+            biases.push(reader.matrix(rows, 1)?);
+
+            let count = reader.u32()?;
+            let mut layer_specs = vec![];
+            for _ in 0..count {
+                layer_specs.push(match reader.u8()? {
+                    0 => LayerSpec::Dropout {
+                        rate: reader.f64()?,
+                    },
+                    1 => LayerSpec::BatchNorm {
+                        momentum: reader.f64()?,
+                        epsilon: reader.f64()?,
+                        scale: reader.matrix(rows, 1)?.data,
+                        shift: reader.matrix(rows, 1)?.data,
+                        running_mean: reader.matrix(rows, 1)?.data,
+                        running_variance: reader.matrix(rows, 1)?.data,
+                    },
+                    tag => return Err(ModelError::Invalid(format!("layer tag {tag}"))),
+                });
+            }
+            inserted.push(layer_specs);
+        }
+
+        if !reader.bytes.is_empty() {
+            return Err(ModelError::Invalid(format!(
+                "{} trailing bytes",
+                reader.bytes.len()
+            )));
+        }
+
+        let mut network = build(
+            layers,
+            activations,
+            loss,
+            learning_rate,
+            weights,
+            biases,
+            inserted,
+        )?;
+        network.set_weight_decay(weight_decay);
+        network.set_schedule(schedule);
+        Ok(network)
+    }
+
+    /// Saves the parameters, inserted layers, loss, learning rate, weight decay and
+    /// schedule. The optimizer and its state are not saved: a loaded network uses a
+    /// fresh [`Sgd`](crate::Sgd), see [`Network::set_optimizer`]. Nor is the RNG: call
+    /// [`Network::set_seed`] on the loaded network for reproducible training.
+    pub fn save(&self, path: impl AsRef<Path>, format: ModelFormat) -> Result<(), ModelError> {
+        let bytes = match format {
+            ModelFormat::Json => self.to_json()?.into_bytes(),
+            ModelFormat::Binary => self.to_bytes(),
+        };
+        std::fs::write(path, bytes)?;
+        Ok(())
+    }
+
+    /// Loads a network saved in either format, told apart by the binary magic.
+    pub fn load(path: impl AsRef<Path>) -> Result<Network, ModelError> {
+        let bytes = std::fs::read(path)?;
+
+        if bytes.starts_with(BINARY_MAGIC) {
+            Network::from_bytes(&bytes)
+        } else {
+            let json = String::from_utf8(bytes).map_err(|_| ModelError::UnknownFormat)?;
+            Network::from_json(&json)
+        }
+    }
+}
+
+fn check_version(found: u32) -> Result<(), ModelError> {
+    if found == FORMAT_VERSION {
+        Ok(())
+    } else {
+        Err(ModelError::UnsupportedVersion {
+            found,
+            expected: FORMAT_VERSION,
+        })
+    }
+}
+
+fn tag<T>(table: &[T], is: impl Fn(&T) -> bool) -> u8 {
+    table.iter().position(is).expect("every variant has a tag") as u8
+}
+
+/// Checks every shape against `layers` before handing the parts to the network.
+fn build(
+    layers: Vec<usize>,
+    activations: Vec<Activation>,
+    loss: Loss,
+    learning_rate: f64,
+    weights: Vec<Matrix>,
+    biases: Vec<Matrix>,
+    inserted: Vec<Vec<LayerSpec>>,
+) -> Result<Network, ModelError> {
+    if layers.len() < 2 || layers.contains(&0) {
+        return Err(ModelError::Invalid(format!("layer sizes {layers:?}")));
+    }
+    let layer_count = layers.len() - 1;
+    for (what, count) in [
+        ("inserted layer lists", inserted.len()),
+        ("activations", activations.len()),
+        ("weights", weights.len()),
+        ("biases", biases.len()),
+    ] {
+        if count != layer_count {
+            return Err(ModelError::Invalid(format!(
+                "{count} {what} for {layer_count} layers"
+            )));
+        }
+    }
+
+    for i in 0..layer_count {
+        check_shape(
+            &format!("weights[{i}]"),
+            &weights[i],
+            (layers[i + 1], layers[i]),
+        )?;
+        check_shape(&format!("biases[{i}]"), &biases[i], (layers[i + 1], 1))?;
+    }
+
+    if inserted.last().is_some_and(|last| !last.is_empty()) {
+        return Err(ModelError::Invalid(
+            "inserted layers after the output layer".to_string(),
+        ));
+    }
+    let inserted = inserted
+        .into_iter()
+        .zip(&layers[1..])
+        .enumerate()
+        .map(|(i, (specs, &features))| {
+            specs
+                .into_iter()
+                .map(|spec| layer_from_spec(spec, features, i))
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Network::from_parts(
+        layers,
+        activations,
+        loss,
+        learning_rate,
+        weights,
+        biases,
+        inserted,
+    ))
+}
+
+/// `features` is the size of dense layer `i`, which the layer follows.
+fn layer_from_spec(spec: LayerSpec, features: usize, i: usize) -> Result<Layer, ModelError> {
+    match spec {
+        LayerSpec::Dropout { rate } => {
+            if !(0.0..1.0).contains(&rate) {
+                return Err(ModelError::Invalid(format!("dropout rate {rate}")));
+            }
+            Ok(Layer::Dropout(Dropout::new(rate)))
+        }
+        LayerSpec::BatchNorm {
+            momentum,
+            epsilon,
+            scale,
+            shift,
+            running_mean,
+            running_variance,
+        } => {
+            for (name, values) in [
+                ("scale", &scale),
+                ("shift", &shift),
+                ("running_mean", &running_mean),
+                ("running_variance", &running_variance),
+            ] {
+                if values.len() != features {
+                    return Err(ModelError::ShapeMismatch {
+                        what: format!("batch_norm[{i}].{name}"),
+                        expected: (features, 1),
+                        found: (values.len(), 1),
+                    });
+                }
+            }
+
+            let mut batch_norm = BatchNorm::new(features);
+            batch_norm.momentum = momentum;
+            batch_norm.epsilon = epsilon;
+            batch_norm.scale = Matrix::from(scale);
+            batch_norm.shift = Matrix::from(shift);
+            batch_norm.running_mean = running_mean;
+            batch_norm.running_variance = running_variance;
+            Ok(Layer::BatchNorm(batch_norm))
+        }
+    }
+}
+
+impl From<&Layer> for LayerSpec {
+    fn from(layer: &Layer) -> Self {
+        match layer {
+            Layer::Dropout(dropout) => LayerSpec::Dropout { rate: dropout.rate },
+            Layer::BatchNorm(batch_norm) => LayerSpec::BatchNorm {
+                momentum: batch_norm.momentum,
+                epsilon: batch_norm.epsilon,
+                scale: batch_norm.scale.data.clone(),
+                shift: batch_norm.shift.data.clone(),
+                running_mean: batch_norm.running_mean.clone(),
+                running_variance: batch_norm.running_variance.clone(),
+            },
+        }
+    }
+}
+
+impl From<LearningRateSchedule> for ScheduleSpec {
+    fn from(schedule: LearningRateSchedule) -> Self {
+        match schedule {
+            LearningRateSchedule::Constant => ScheduleSpec::Constant,
+            LearningRateSchedule::Step { step_size, gamma } => {
+                ScheduleSpec::Step { step_size, gamma }
+            }
+            LearningRateSchedule::Exponential { gamma } => ScheduleSpec::Exponential { gamma },
+            LearningRateSchedule::Cosine { epochs, min_rate } => {
+                ScheduleSpec::Cosine { epochs, min_rate }
+            }
+        }
+    }
+}
+
+impl From<ScheduleSpec> for LearningRateSchedule {
+    fn from(spec: ScheduleSpec) -> Self {
+        match spec {
+            ScheduleSpec::Constant => LearningRateSchedule::Constant,
+            ScheduleSpec::Step { step_size, gamma } => {
+                LearningRateSchedule::Step { step_size, gamma }
+            }
+            ScheduleSpec::Exponential { gamma } => LearningRateSchedule::Exponential { gamma },
+            ScheduleSpec::Cosine { epochs, min_rate } => {
+                LearningRateSchedule::Cosine { epochs, min_rate }
+            }
+        }
+    }
+}
+
+fn check_shape(what: &str, matrix: &Matrix, expected: (usize, usize)) -> Result<(), ModelError> {
+    if (matrix.rows, matrix.cols) == expected {
+        Ok(())
+    } else {
+        Err(ModelError::ShapeMismatch {
+            what: what.to_string(),
+            expected,
+            found: (matrix.rows, matrix.cols),
+        })
+    }
+}
+
+impl From<&Matrix> for MatrixSpec {
+    fn from(matrix: &Matrix) -> Self {
+        MatrixSpec {
+            rows: matrix.rows,
+            cols: matrix.cols,
+            data: matrix.data.clone(),
+        }
+    }
+}
+
+impl TryFrom<MatrixSpec> for Matrix {
+    type Error = ModelError;
+
+    fn try_from(spec: MatrixSpec) -> Result<Self, Self::Error> {
+        if spec.rows.checked_mul(spec.cols) != Some(spec.data.len()) {
+            return Err(ModelError::Invalid(format!(
+                "{}x{} matrix with {} values",
+                spec.rows,
+                spec.cols,
+                spec.data.len()
+            )));
+        }
+
+        Ok(Matrix {
+            rows: spec.rows,
+            cols: spec.cols,
+            data: spec.data,
+        })
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], ModelError> {
+        if self.bytes.len() < n {
+            return Err(ModelError::Truncated);
+        }
+        let (head, tail) = self.bytes.split_at(n);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    fn u8(&mut self) -> Result<u8, ModelError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, ModelError> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn f64(&mut self) -> Result<f64, ModelError> {
+        Ok(f64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    /// Tag then parameters, the epoch count (if any) as a `u32` before the `f64`s.
+    fn schedule(&mut self) -> Result<LearningRateSchedule, ModelError> {
+        Ok(match self.u8()? {
+            0 => LearningRateSchedule::Constant,
+            1 => LearningRateSchedule::Step {
+                step_size: self.u32()?,
+                gamma: self.f64()?,
+            },
+            2 => LearningRateSchedule::Exponential { gamma: self.f64()? },
+            3 => LearningRateSchedule::Cosine {
+                epochs: self.u32()?,
+                min_rate: self.f64()?,
+            },
+            tag => return Err(ModelError::Invalid(format!("schedule tag {tag}"))),
+        })
+    }
+
+    fn matrix(&mut self, rows: usize, cols: usize) -> Result<Matrix, ModelError> {
+        let len = rows.checked_mul(cols).ok_or(ModelError::Truncated)?;
+        let byte_len = len.checked_mul(8).ok_or(ModelError::Truncated)?;
+
+        let data = self
+            .take(byte_len)?
+            .chunks_exact(8)
+            .map(|chunk| f64::from_le_bytes(chunk.try_into().unwrap()))
+            .collect();
+
+        Ok(Matrix { rows, cols, data })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Dataset, Mode, TrainConfig};
+
+    fn network() -> Network {
+        let mut network = Network::builder(3)
+            .layer(4, Activation::LeakyRelu(0.05))
+            .batch_norm()
+            .dropout(0.25)
+            .layer(2, Activation::Softmax)
+            .loss(Loss::CategoricalCrossEntropy)
+            .learning_rate(0.3)
+            .weight_decay(0.01)
+            .schedule(LearningRateSchedule::Step {
+                step_size: 10,
+                gamma: 0.5,
+            })
+            .seed(0)
+            .build();
+        // Moves the batch norm statistics away from their defaults.
+        network.set_mode(Mode::Train);
+        network.feed_forward(Matrix::from_columns(&[
+            vec![1.0, 0.0, 2.0],
+            vec![-1.0, 0.5, 0.0],
+        ]));
+        network.set_mode(Mode::Eval);
+        network
+    }
+
+    fn assert_same(a: &mut Network, b: &mut Network) {
+        a.set_mode(Mode::Eval);
+        b.set_mode(Mode::Eval);
+        assert_eq!(a.layers(), b.layers());
+        assert_eq!(a.activations(), b.activations());
+        assert_eq!(a.loss_fn(), b.loss_fn());
+        assert_eq!(a.learning_rate(), b.learning_rate());
+        assert_eq!(a.weight_decay(), b.weight_decay());
+        assert_eq!(a.schedule(), b.schedule());
+
+        let inputs = Matrix::from(vec![0.2, -0.4, 0.9]);
+        assert_eq!(
+            a.feed_forward(inputs.clone()).data,
+            b.feed_forward(inputs).data
+        );
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let mut original = network();
+
+        let mut loaded = Network::from_json(&original.to_json().unwrap()).unwrap();
+
+        assert_same(&mut original, &mut loaded);
+    }
+
+    #[test]
+    fn binary_round_trip() {
+        let mut original = network();
+        let bytes = original.to_bytes();
+
+        let mut loaded = Network::from_bytes(&bytes).unwrap();
+
+        assert_same(&mut original, &mut loaded);
+        assert_eq!(
+            bytes.len(),
+            4 + 4
+                + 4
+                + 3 * 4
+                + 1
+                + 8
+                + 1
+                + 1
+                + 8
+                + 8
+                + (1 + 4 + 8)
+                + (12 + 4 + 8 + 2) * 8
+                + 2 * 4
+                + (1 + 8)
+                + (1 + 2 * 8 + 4 * 4 * 8)
+        );
+    }
+
+    #[test]
+    fn save_and_load_detect_format() {
+        let dir = std::env::temp_dir();
+        for (format, name) in [
+            (ModelFormat::Json, "video_14_NN_test.json"),
+            (ModelFormat::Binary, "video_14_NN_test.bin"),
+        ] {
+            let path = dir.join(name);
+            let mut original = network();
+            original.save(&path, format).unwrap();
+
+            let mut loaded = Network::load(&path).unwrap();
+            std::fs::remove_file(&path).unwrap();
+
+            assert_same(&mut original, &mut loaded);
+        }
+    }
+
+    #[test]
+    fn seeded_loads_train_identically() {
+        let json = network().to_json().unwrap();
+        let dataset = Dataset::from_samples(
+            &[
+                vec![0.5, -0.2, 0.9],
+                vec![-1.0, 0.3, 0.1],
+                vec![0.2, 0.8, -0.6],
+            ],
+            &[vec![1.0, 0.0], vec![0.0, 1.0], vec![0.0, 1.0]],
+        );
+        let config = TrainConfig {
+            epochs: 5,
+            batch_size: 2,
+            ..Default::default()
+        };
+        let train = || {
+            let mut network = Network::from_json(&json).unwrap();
+            network.set_seed(11);
+            let history = network.fit(&dataset, None, &config, |_| {});
+            (history.epochs, network.weights()[0].data.clone())
+        };
+
+        assert_eq!(train(), train());
+    }
+
+    #[test]
+    fn batch_norm_shape_mismatch() {
+        let mut file: serde_json::Value =
+            serde_json::from_str(&network().to_json().unwrap()).unwrap();
+        file["inserted"][0][0]["scale"] = serde_json::json!([1.0]);
+
+        let err = Network::from_json(&file.to_string()).err().unwrap();
+
+        assert!(
+            matches!(&err, ModelError::ShapeMismatch { what, .. } if what == "batch_norm[0].scale"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn version_mismatch() {
+        let json = network().to_json().unwrap().replacen(
+            &format!("\"version\": {FORMAT_VERSION}"),
+            "\"version\": 99",
+            1,
+        );
+        let mut bytes = network().to_bytes();
+        bytes[4] = 99;
+
+        for err in [
+            Network::from_json(&json).err().unwrap(),
+            Network::from_bytes(&bytes).err().unwrap(),
+        ] {
+            assert!(matches!(
+                err,
+                ModelError::UnsupportedVersion { found: 99, .. }
+            ));
+        }
+    }
+
+    #[test]
+    fn shape_mismatch() {
+        let mut file: serde_json::Value =
+            serde_json::from_str(&network().to_json().unwrap()).unwrap();
+        file["layers"][1] = 5.into();
+
+        let err = Network::from_json(&file.to_string()).err().unwrap();
+
+        assert!(matches!(err, ModelError::ShapeMismatch { .. }), "{err}");
+    }
+
+    #[test]
+    fn corrupt_binary() {
+        let bytes = network().to_bytes();
+
+        assert!(matches!(
+            Network::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(ModelError::Truncated)
+        ));
+        assert!(matches!(
+            Network::from_bytes(b"nope"),
+            Err(ModelError::UnknownFormat)
+        ));
+
+        let mut bytes = bytes;
+        bytes.push(0);
+        assert!(matches!(
+            Network::from_bytes(&bytes),
+            Err(ModelError::Invalid(_))
+        ));
+    }
+}
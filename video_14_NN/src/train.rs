@@ -0,0 +1,432 @@
+use rand::{SeedableRng, rngs::StdRng, seq::SliceRandom};
+
+use crate::{Layer, Matrix, Mode, Network};
+
+/// Samples stored column-wise: column `j` of `inputs` pairs with column `j` of `targets`.
+#[derive(Debug, Clone)]
+pub struct Dataset {
+    pub inputs: Matrix,
+    pub targets: Matrix,
+}
+
+impl Dataset {
+    pub fn new(inputs: Matrix, targets: Matrix) -> Self {
+        assert_eq!(
+            inputs.cols, targets.cols,
+            "Inputs and targets must have the same number of samples"
+        );
+        Dataset { inputs, targets }
+    }
+
+    /// One `Vec` per sample, as taken by [`Network::train`].
+    pub fn from_samples(inputs: &[Vec<f64>], targets: &[Vec<f64>]) -> Self {
+        Dataset::new(Matrix::from_columns(inputs), Matrix::from_columns(targets))
+    }
+
+    pub fn len(&self) -> usize {
+        self.inputs.cols
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn select(&self, indices: &[usize]) -> Dataset {
+        Dataset {
+            inputs: self.inputs.select_cols(indices),
+            targets: self.targets.select_cols(indices),
+        }
+    }
+
+    /// Consecutive batches of at most `batch_size` samples, taken in `order`.
+    pub fn batches<'a>(
+        &'a self,
+        order: &'a [usize],
+        batch_size: usize,
+    ) -> impl Iterator<Item = Dataset> + 'a {
+        order
+            .chunks(batch_size.max(1))
+            .map(|indices| self.select(indices))
+    }
+
+    /// Shuffles the samples and splits off `validation_fraction` of them as `(train, validation)`.
+    ///
+    /// Panics unless `validation_fraction` is within `0.0..=1.0`.
+    pub fn split(&self, validation_fraction: f64, seed: u64) -> (Dataset, Dataset) {
+        assert!(
+            (0.0..=1.0).contains(&validation_fraction),
+            "Validation fraction must be within 0.0..=1.0, got {validation_fraction}"
+        );
+
+        let mut indices: Vec<usize> = (0..self.len()).collect();
+        indices.shuffle(&mut StdRng::seed_from_u64(seed));
+
+        let validation_len = (self.len() as f64 * validation_fraction).round() as usize;
+        let (validation, train) = indices.split_at(validation_len);
+
+        (self.select(train), self.select(validation))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TrainConfig {
+    pub epochs: u32,
+    pub batch_size: usize,
+    pub shuffle: bool,
+    /// Seed of the shuffling RNG, `None` to draw it from the network's own RNG.
+    pub seed: Option<u64>,
+    pub early_stopping: Option<EarlyStopping>,
+}
+
+impl Default for TrainConfig {
+    fn default() -> Self {
+        TrainConfig {
+            epochs: 100,
+            batch_size: 32,
+            shuffle: true,
+            seed: None,
+            early_stopping: None,
+        }
+    }
+}
+
+/// Stops once the monitored loss (validation if given, training otherwise) has not
+/// improved by more than `min_delta` for `patience` epochs.
+#[derive(Debug, Clone)]
+pub struct EarlyStopping {
+    pub patience: u32,
+    pub min_delta: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EpochMetrics {
+    pub epoch: u32,
+    pub learning_rate: f64,
+    pub loss: f64,
+    pub accuracy: f64,
+    pub val_loss: Option<f64>,
+    pub val_accuracy: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct History {
+    pub epochs: Vec<EpochMetrics>,
+    pub stopped_early: bool,
+}
+
+impl History {
+    pub fn last(&self) -> Option<&EpochMetrics> {
+        self.epochs.last()
+    }
+}
+
+impl Network {
+    /// Mini-batch gradient descent over `train`, calling `on_epoch` after each epoch.
+    ///
+    /// Batch statistics need at least two samples, so with a batch norm layer a lone
+    /// trailing sample is merged into the previous batch, and a `batch_size` (or
+    /// dataset) of 1 panics.
+    pub fn fit(
+        &mut self,
+        train: &Dataset,
+        validation: Option<&Dataset>,
+        config: &TrainConfig,
+        mut on_epoch: impl FnMut(&EpochMetrics),
+    ) -> History {
+        let mut rng = match config.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_rng(self.rng()),
+        };
+        let batch_size = config.batch_size.max(1);
+        let has_batch_norm = self
+            .inserted_layers()
+            .iter()
+            .flatten()
+            .any(|layer| matches!(layer, Layer::BatchNorm(_)));
+        assert!(
+            !has_batch_norm || batch_size.min(train.len()) != 1,
+            "Batch norm needs batches of at least 2 samples, got a batch size of 1"
+        );
+        // A lone trailing sample would give batch norm a zero variance, so it joins
+        // the batch before it.
+        let merge_last =
+            has_batch_norm && train.len() > batch_size && train.len() % batch_size == 1;
+        let mut indices: Vec<usize> = (0..train.len()).collect();
+
+        let mut history = History::default();
+        let mut best_loss = f64::INFINITY;
+        let mode = self.mode();
+        let mut epochs_without_improvement = 0;
+
+        for epoch in 1..=config.epochs {
+            self.set_epoch(epoch - 1);
+            let learning_rate = self.current_learning_rate();
+
+            if config.shuffle {
+                indices.shuffle(&mut rng);
+            }
+
+            self.set_mode(Mode::Train);
+            let (order, last) = if merge_last {
+                indices.split_at(train.len() - batch_size - 1)
+            } else {
+                (&indices[..], &[][..])
+            };
+            let last = (!last.is_empty()).then(|| train.select(last));
+            for batch in train.batches(order, batch_size).chain(last) {
+                self.feed_forward(batch.inputs);
+                self.back_propogate(batch.targets);
+            }
+
+            let (loss, accuracy) = self.evaluate(train);
+            let (val_loss, val_accuracy) = match validation.map(|v| self.evaluate(v)) {
+                Some((val_loss, val_accuracy)) => (Some(val_loss), Some(val_accuracy)),
+                None => (None, None),
+            };
+            let metrics = EpochMetrics {
+                epoch,
+                learning_rate,
+                loss,
+                accuracy,
+                val_loss,
+                val_accuracy,
+            };
+            on_epoch(&metrics);
+            history.epochs.push(metrics);
+
+            if let Some(early_stopping) = &config.early_stopping {
+                let monitored = val_loss.unwrap_or(loss);
+                if monitored < best_loss - early_stopping.min_delta {
+                    best_loss = monitored;
+                    epochs_without_improvement = 0;
+                } else {
+                    epochs_without_improvement += 1;
+                    if epochs_without_improvement >= early_stopping.patience {
+                        history.stopped_early = true;
+                        break;
+                    }
+                }
+            }
+        }
+
+        self.set_mode(mode);
+        history
+    }
+
+    /// Loss and accuracy over a whole dataset, computed in [`Mode::Eval`].
+    ///
+    /// Accuracy compares the argmax of outputs and targets, or thresholds at 0.5 for
+    /// single-output networks.
+    pub fn evaluate(&mut self, dataset: &Dataset) -> (f64, f64) {
+        let mode = self.mode();
+        self.set_mode(Mode::Eval);
+        let outputs = self.feed_forward(dataset.inputs.clone());
+        self.set_mode(mode);
+
+        let loss = self.loss_fn().loss(&outputs, &dataset.targets);
+        (loss, accuracy(&outputs, &dataset.targets))
+    }
+}
+
+pub fn accuracy(outputs: &Matrix, targets: &Matrix) -> f64 {
+    if outputs.cols == 0 {
+        return 0.0;
+    }
+
+    let correct = (0..outputs.cols)
+        .filter(|&j| {
+            if outputs.rows == 1 {
+                (outputs.data[j] >= 0.5) == (targets.data[j] >= 0.5)
+            } else {
+                argmax(&outputs.column(j)) == argmax(&targets.column(j))
+            }
+        })
+        .count();
+
+    correct as f64 / outputs.cols as f64
+}
+
+/// `counts[actual][predicted]` over the columns of `outputs` and one-hot `targets`.
+pub fn confusion_matrix(outputs: &Matrix, targets: &Matrix) -> Vec<Vec<usize>> {
+    let classes = targets.rows;
+    let mut counts = vec![vec![0; classes]; classes];
+
+    for j in 0..outputs.cols {
+        let actual = argmax(&targets.column(j));
+        let predicted = argmax(&outputs.column(j));
+        counts[actual][predicted] += 1;
+    }
+
+    counts
+}
+
+pub fn argmax(values: &[f64]) -> usize {
+    values
+        .iter()
+        .enumerate()
+        .max_by(|a, b| a.1.total_cmp(b.1))
+        .map_or(0, |(i, _)| i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Activation, Loss};
+
+    fn blobs() -> Dataset {
+        let mut inputs = vec![];
+        let mut targets = vec![];
+        for i in 0..40 {
+            let x = (i % 10) as f64 / 10.0;
+            if i % 2 == 0 {
+                inputs.push(vec![x, 1.0 - x * 0.5]);
+                targets.push(vec![1.0, 0.0]);
+            } else {
+                inputs.push(vec![-x, -1.0 + x * 0.5]);
+                targets.push(vec![0.0, 1.0]);
+            }
+        }
+        Dataset::from_samples(&inputs, &targets)
+    }
+
+    fn network() -> Network {
+        Network::builder(2)
+            .layer(4, Activation::Tanh)
+            .layer(2, Activation::Softmax)
+            .loss(Loss::CategoricalCrossEntropy)
+            .learning_rate(0.5)
+            .seed(0)
+            .build()
+    }
+
+    #[test]
+    fn fit_reports_history_through_callback() {
+        let (train, validation) = blobs().split(0.25, 7);
+        let config = TrainConfig {
+            epochs: 30,
+            batch_size: 8,
+            seed: Some(1),
+            ..Default::default()
+        };
+        let mut seen = vec![];
+
+        let history = network().fit(&train, Some(&validation), &config, |m| seen.push(m.epoch));
+
+        assert_eq!(train.len(), 30);
+        assert_eq!(validation.len(), 10);
+        assert_eq!(seen, (1..=30).collect::<Vec<_>>());
+        assert_eq!(history.epochs.len(), 30);
+        assert!(history.epochs[0].val_loss.is_some());
+        let last = history.last().unwrap();
+        assert!(last.loss < history.epochs[0].loss);
+        assert_eq!(last.val_accuracy, Some(1.0));
+    }
+
+    #[test]
+    fn early_stopping_on_plateau() {
+        let data = blobs();
+        let config = TrainConfig {
+            epochs: 10_000,
+            batch_size: 40,
+            seed: Some(3),
+            early_stopping: Some(EarlyStopping {
+                patience: 5,
+                min_delta: 1e-3,
+            }),
+            ..Default::default()
+        };
+
+        let history = network().fit(&data, Some(&data), &config, |_| {});
+
+        assert!(history.stopped_early);
+        assert!(history.epochs.len() < 10_000);
+    }
+
+    #[test]
+    fn batches_cover_every_sample() {
+        let data = blobs();
+        let order: Vec<usize> = (0..data.len()).collect();
+
+        let sizes: Vec<usize> = data.batches(&order, 16).map(|b| b.len()).collect();
+        let last = data.batches(&order, 16).last().unwrap();
+
+        assert_eq!(sizes, [16, 16, 8]);
+        assert_eq!(last.inputs.column(7), data.inputs.column(39));
+    }
+
+    #[test]
+    fn batch_norm_merges_single_sample_remainder() {
+        let inputs: Vec<Vec<f64>> = (0..33)
+            .map(|j| vec![(j as f64 * 0.37).sin(), (j as f64 * 0.61).cos()])
+            .collect();
+        let targets: Vec<Vec<f64>> = (0..33)
+            .map(|j| vec![(j % 2) as f64, ((j + 1) % 2) as f64])
+            .collect();
+        let data = Dataset::from_samples(&inputs, &targets);
+        let build = || {
+            Network::builder(2)
+                .layer(3, Activation::Tanh)
+                .batch_norm()
+                .layer(2, Activation::Softmax)
+                .loss(Loss::CategoricalCrossEntropy)
+                .seed(0)
+                .build()
+        };
+        let (mut network, mut expected) = (build(), build());
+        let config = TrainConfig {
+            epochs: 1,
+            shuffle: false,
+            ..Default::default()
+        };
+
+        network.fit(&data, None, &config, |_| {});
+        expected.set_mode(Mode::Train);
+        expected.feed_forward(data.inputs.clone());
+        expected.back_propogate(data.targets.clone());
+
+        for (actual, expected) in network.weights().iter().zip(expected.weights()) {
+            assert_eq!(actual.data, expected.data);
+        }
+        let [Layer::BatchNorm(actual)] = &network.inserted_layers()[0][..] else {
+            unreachable!()
+        };
+        let [Layer::BatchNorm(expected)] = &expected.inserted_layers()[0][..] else {
+            unreachable!()
+        };
+        assert_eq!(actual.running_variance, expected.running_variance);
+    }
+
+    #[test]
+    fn confusion_matrix_counts_predictions() {
+        let outputs = Matrix::from_columns(&[
+            vec![0.9, 0.1],
+            vec![0.2, 0.8],
+            vec![0.6, 0.4],
+            vec![0.3, 0.7],
+        ]);
+        let targets = Matrix::from_columns(&[
+            vec![1.0, 0.0],
+            vec![0.0, 1.0],
+            vec![0.0, 1.0],
+            vec![0.0, 1.0],
+        ]);
+
+        assert_eq!(confusion_matrix(&outputs, &targets), [[1, 0], [1, 2]]);
+    }
+
+    #[test]
+    fn split_is_seeded() {
+        let data = blobs();
+
+        let (a, _) = data.split(0.5, 42);
+        let (b, _) = data.split(0.5, 42);
+
+        assert_eq!(a.inputs.data, b.inputs.data);
+    }
+
+    #[test]
+    #[should_panic(expected = "Validation fraction must be within 0.0..=1.0, got NaN")]
+    fn split_rejects_invalid_fraction() {
+        blobs().split(f64::NAN, 0);
+    }
+}
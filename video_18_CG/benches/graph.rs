@@ -0,0 +1,44 @@
+use criterion::{Criterion, criterion_group, criterion_main};
+use std::hint::black_box;
+use video_18_CG::{Value, calculat_grad};
+
+const NODES: usize = 1_000_000;
+
+/// `NODES / 2` leaves summed into a chain of `NODES / 2` additions.
+fn long_sum() -> (Vec<Value>, Value) {
+    let leaves: Vec<Value> = (0..NODES / 2).map(|i| Value::new(i as f32)).collect();
+    let sum = leaves.iter().cloned().sum();
+    (leaves, sum)
+}
+
+/// A single leaf pushed through `NODES` unary ops.
+fn deep_chain() -> (Value, Value) {
+    let x = Value::new(0.5);
+    let mut chain = x.clone();
+    for _ in 0..NODES {
+        chain = chain.tanh();
+    }
+    (x, chain)
+}
+
+fn graph(c: &mut Criterion) {
+    let mut group = c.benchmark_group("graph_1e6");
+    group.sample_size(10);
+
+    group.bench_function("build_sum", |b| b.iter(|| black_box(long_sum())));
+    group.bench_function("build_chain", |b| b.iter(|| black_box(deep_chain())));
+
+    let (_leaves, sum) = long_sum();
+    group.bench_function("backward_sum", |b| b.iter(|| sum.backward()));
+    group.bench_function("calculat_grad_sum", |b| b.iter(|| calculat_grad(&sum)));
+    group.bench_function("forward_sum", |b| b.iter(|| black_box(sum.forward())));
+
+    let (_x, chain) = deep_chain();
+    group.bench_function("backward_chain", |b| b.iter(|| chain.backward()));
+    group.bench_function("forward_chain", |b| b.iter(|| black_box(chain.forward())));
+
+    group.finish();
+}
+
+criterion_group!(benches, graph);
+criterion_main!(benches);
@@ -0,0 +1,62 @@
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use std::hint::black_box;
+use video_18_CG::{Tape, Value};
+
+/// `sum(tanh(x_i * w_i))` over `n` pairs of leaves, about `5n` nodes.
+fn rc_graph(n: usize) -> f32 {
+    let terms = (0..n).map(|i| {
+        let x = Value::new(i as f32 / n as f32);
+        let w = Value::new(0.5);
+        (&x * &w).tanh()
+    });
+    let out: Value = terms.sum();
+    out.backward();
+    out.get_data()
+}
+
+fn tape_graph(n: usize) -> f32 {
+    let tape = Tape::with_capacity(5 * n);
+    let mut out = tape.leaf(0.0);
+    for i in 0..n {
+        let x = tape.leaf(i as f32 / n as f32);
+        let w = tape.leaf(0.5);
+        out = out + (x * w).tanh();
+    }
+    tape.backward(out);
+    out.data()
+}
+
+fn build_and_backward(c: &mut Criterion) {
+    let mut group = c.benchmark_group("build_and_backward");
+    group.sample_size(10);
+    for n in [1_000, 200_000] {
+        group.bench_with_input(BenchmarkId::new("rc", n), &n, |b, &n| {
+            b.iter(|| black_box(rc_graph(n)))
+        });
+        group.bench_with_input(BenchmarkId::new("tape", n), &n, |b, &n| {
+            b.iter(|| black_box(tape_graph(n)))
+        });
+    }
+    group.finish();
+}
+
+/// One tape per thread; the `Rc` graph can't be shared or sent, so it has no counterpart.
+fn threads(c: &mut Criterion) {
+    let mut group = c.benchmark_group("tape_threads");
+    group.sample_size(10);
+    for threads in [1, 4] {
+        group.bench_with_input(BenchmarkId::from_parameter(threads), &threads, |b, &t| {
+            b.iter(|| {
+                std::thread::scope(|s| {
+                    for _ in 0..t {
+                        s.spawn(|| black_box(tape_graph(200_000)));
+                    }
+                })
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, build_and_backward, threads);
+criterion_main!(benches);
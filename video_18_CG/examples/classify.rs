@@ -0,0 +1,62 @@
+//! Trains an MLP to separate points inside a circle from those outside it.
+
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use video_18_CG::{MLP, Module, Sgd, Value, calculat_grad};
+
+fn main() {
+    let mut rng = StdRng::seed_from_u64(42);
+
+    // Label +1 inside the circle of radius 0.6, -1 outside.
+    let samples: Vec<([f32; 2], f32)> = (0..100)
+        .map(|_| {
+            let x = [rng.random_range(-1.0..1.0), rng.random_range(-1.0..1.0)];
+            let label = if x[0] * x[0] + x[1] * x[1] < 0.36 {
+                1.0
+            } else {
+                -1.0
+            };
+            (x, label)
+        })
+        .collect();
+
+    let mlp = MLP::new(2, &[16, 16, 1], &mut rng);
+    println!("{} parameters", mlp.parameters().len());
+
+    let epochs = 100;
+    for epoch in 1..=epochs {
+        let scores: Vec<Value> = samples
+            .iter()
+            .map(|(x, _)| mlp.forward(&[Value::new(x[0]), Value::new(x[1])])[0].clone())
+            .collect();
+
+        // Max-margin loss plus L2 regularization.
+        let data_loss: Value = scores
+            .iter()
+            .zip(&samples)
+            .map(|(score, (_, label))| (1.0 - score * *label).relu())
+            .sum::<Value>()
+            / samples.len() as f32;
+        let reg_loss: Value = 1e-4 * mlp.parameters().iter().map(|p| p * p).sum::<Value>();
+        let loss = data_loss + reg_loss;
+
+        let correct = scores
+            .iter()
+            .zip(&samples)
+            .filter(|(score, (_, label))| (score.get_data() > 0.0) == (*label > 0.0))
+            .count();
+
+        mlp.zero_grad();
+        calculat_grad(&loss);
+        // Decays from 1.0 to 0.1 over the run.
+        let learning_rate = 1.0 - 0.9 * (epoch - 1) as f32 / epochs as f32;
+        Sgd::new(learning_rate).step(&mlp);
+
+        if epoch % 10 == 0 || epoch == 1 {
+            println!(
+                "Epoch {epoch:>3}: loss {:.4}, accuracy {:.0}%",
+                loss.get_data(),
+                100.0 * correct as f32 / samples.len() as f32
+            );
+        }
+    }
+}
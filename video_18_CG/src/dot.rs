@@ -0,0 +1,223 @@
+use std::{collections::HashMap, fmt::Write};
+
+use crate::{Op, Value, topological_order};
+
+/// What [`to_dot`] renders.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DotOptions {
+    /// Digits after the decimal point of data and grads.
+    pub precision: usize,
+    pub show_names: bool,
+    pub show_data: bool,
+    pub show_grad: bool,
+    /// Fills value nodes green for positive and red for negative grads, the stronger
+    /// the larger the grad relative to the largest one in the graph.
+    pub color_by_grad: bool,
+    /// Lays the graph out left to right instead of top to bottom.
+    pub left_to_right: bool,
+}
+
+impl Default for DotOptions {
+    fn default() -> Self {
+        DotOptions {
+            precision: 4,
+            show_names: true,
+            show_data: true,
+            show_grad: true,
+            color_by_grad: false,
+            left_to_right: true,
+        }
+    }
+}
+
+/// The graph of `root` in Graphviz DOT syntax, micrograd style: every value is a
+/// record node and every op a separate node between its operands and its result.
+///
+/// Nodes are numbered in topological order, so the text only depends on the graph.
+pub fn to_dot(root: &Value, options: &DotOptions) -> String {
+    let order = topological_order(&[root]);
+    let index: HashMap<usize, usize> = order.iter().enumerate().map(|(i, v)| (v.id, i)).collect();
+    let max_grad = order.iter().map(|v| v.get_grad().abs()).fold(0.0, f32::max);
+
+    let mut dot = String::from("digraph {\n");
+    if options.left_to_right {
+        dot.push_str("    rankdir=LR;\n");
+    }
+    for (i, value) in order.iter().enumerate() {
+        let mut fields = Vec::new();
+        if options.show_names
+            && let Some(name) = value.name()
+        {
+            fields.push(escape(&name));
+        }
+        if options.show_data {
+            fields.push(format!("data {:.*}", options.precision, value.get_data()));
+        }
+        if options.show_grad {
+            fields.push(format!("grad {:.*}", options.precision, value.get_grad()));
+        }
+        let mut attrs = format!("shape=record, label=\"{{ {} }}\"", fields.join(" | "));
+        if options.color_by_grad {
+            let color = grad_color(value.get_grad(), max_grad);
+            write!(attrs, ", style=filled, fillcolor=\"{color}\"").unwrap();
+        }
+        writeln!(dot, "    n{i} [{attrs}];").unwrap();
+
+        if let Some(symbol) = op_symbol(&value.op) {
+            writeln!(dot, "    n{i}_op [label=\"{}\"];", escape_quoted(&symbol)).unwrap();
+            for operand in value.op.operands() {
+                let j = index[&operand.id];
+                writeln!(dot, "    n{j} -> n{i}_op;").unwrap();
+            }
+            writeln!(dot, "    n{i}_op -> n{i};").unwrap();
+        }
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+impl Value {
+    /// [`to_dot`] with the default options.
+    pub fn to_dot(&self) -> String {
+        to_dot(self, &DotOptions::default())
+    }
+}
+
+fn op_symbol(op: &Op) -> Option<String> {
+    let symbol = match op {
+        Op::None => return None,
+        Op::Add(_, _) => "+".to_string(),
+        Op::Sub(_, _) => "-".to_string(),
+        Op::Mul(_, _) => "*".to_string(),
+        Op::Div(_, _) => "/".to_string(),
+        Op::Neg(_) => "neg".to_string(),
+        Op::Pow(_, exponent) => format!("^{exponent}"),
+        Op::Exp(_) => "exp".to_string(),
+        Op::Log(_) => "log".to_string(),
+        Op::Relu(_) => "relu".to_string(),
+        Op::Sigmoid(_) => "sigmoid".to_string(),
+        Op::Tanh(_) => "tanh".to_string(),
+    };
+    Some(symbol)
+}
+
+/// White for a zero grad, towards green or red as `|grad|` approaches `max_grad`.
+fn grad_color(grad: f32, max_grad: f32) -> String {
+    let strength = if max_grad > 0.0 {
+        grad.abs() / max_grad
+    } else {
+        0.0
+    };
+    let target = if grad >= 0.0 {
+        [0x66, 0xcc, 0x66]
+    } else {
+        [0xee, 0x66, 0x66]
+    };
+    let [r, g, b] = target.map(|c: u8| (255.0 - (255.0 - c as f32) * strength).round() as u8);
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+/// Escapes characters with a meaning in record labels.
+fn escape(text: &str) -> String {
+    escape_chars(text, &['"', '\\', '{', '}', '|', '<', '>'])
+}
+
+/// Escapes characters with a meaning in plain quoted labels.
+fn escape_quoted(text: &str) -> String {
+    escape_chars(text, &['"', '\\'])
+}
+
+fn escape_chars(text: &str, special: &[char]) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if special.contains(&c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_values_and_ops() {
+        let a = Value::named(2.0, "a");
+        let b = Value::named(-0.25, "b");
+        let c = (&a * &b).tanh();
+        c.set_name("c");
+        c.backward();
+
+        let options = DotOptions {
+            precision: 2,
+            ..DotOptions::default()
+        };
+
+        let expected = "\
+digraph {
+    rankdir=LR;
+    n0 [shape=record, label=\"{ a | data 2.00 | grad -0.20 }\"];
+    n1 [shape=record, label=\"{ b | data -0.25 | grad 1.57 }\"];
+    n2 [shape=record, label=\"{ data -0.50 | grad 0.79 }\"];
+    n2_op [label=\"*\"];
+    n0 -> n2_op;
+    n1 -> n2_op;
+    n2_op -> n2;
+    n3 [shape=record, label=\"{ c | data -0.46 | grad 1.00 }\"];
+    n3_op [label=\"tanh\"];
+    n2 -> n3_op;
+    n3_op -> n3;
+}
+";
+        assert_eq!(to_dot(&c, &options), expected);
+    }
+
+    #[test]
+    fn escapes_only_what_the_label_kind_needs() {
+        let text = r#"a|{b}<c> "d" \e"#;
+
+        assert_eq!(escape(text), r#"a\|\{b\}\<c\> \"d\" \\e"#);
+        assert_eq!(escape_quoted(text), r#"a|{b}<c> \"d\" \\e"#);
+    }
+
+    #[test]
+    fn options_change_labels() {
+        let x = Value::named(0.25, "x|{y}");
+        let out = x.pow(2.0) + 1.0;
+        out.backward();
+
+        let options = DotOptions {
+            precision: 1,
+            show_data: false,
+            color_by_grad: true,
+            left_to_right: false,
+            ..DotOptions::default()
+        };
+        let dot = to_dot(&out, &options);
+
+        assert!(!dot.contains("rankdir"));
+        assert!(!dot.contains("data"));
+        assert!(dot.contains("label=\"{ x\\|\\{y\\} | grad 0.5 }\""));
+        assert!(dot.contains("[label=\"^2\"]"));
+        assert!(dot.contains("fillcolor=\"#66cc66\""));
+        assert!(dot.contains("fillcolor=\"#b3e6b3\""));
+
+        let unnamed = to_dot(
+            &out,
+            &DotOptions {
+                show_names: false,
+                ..DotOptions::default()
+            },
+        );
+        assert!(!unnamed.contains("x\\|"));
+    }
+
+    #[test]
+    fn grad_colors() {
+        assert_eq!(grad_color(0.0, 0.0), "#ffffff");
+        assert_eq!(grad_color(2.0, 2.0), "#66cc66");
+        assert_eq!(grad_color(-2.0, 2.0), "#ee6666");
+    }
+}
@@ -0,0 +1,306 @@
+/// Forward-mode counterpart of [`Value`](crate::Value): a number carried together
+/// with its derivative along one input direction.
+///
+/// No graph is recorded, so one pass gives the derivative w.r.t. one input; see
+/// [`gradient`] for all of them.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Dual {
+    pub value: f32,
+    pub derivative: f32,
+}
+
+impl Dual {
+    pub fn new(value: f32, derivative: f32) -> Self {
+        Dual { value, derivative }
+    }
+
+    pub fn constant(value: f32) -> Self {
+        Dual::new(value, 0.0)
+    }
+
+    /// The input being differentiated against.
+    pub fn variable(value: f32) -> Self {
+        Dual::new(value, 1.0)
+    }
+
+    /// Applies `f` with its derivative `df` at the current value.
+    fn chain(self, f: f32, df: f32) -> Dual {
+        Dual::new(f, df * self.derivative)
+    }
+
+    /// Raised to a constant exponent.
+    pub fn pow(self, exponent: f32) -> Dual {
+        self.chain(
+            self.value.powf(exponent),
+            exponent * self.value.powf(exponent - 1.0),
+        )
+    }
+
+    pub fn exp(self) -> Dual {
+        let e = self.value.exp();
+        self.chain(e, e)
+    }
+
+    /// Natural logarithm.
+    pub fn log(self) -> Dual {
+        self.chain(self.value.ln(), 1.0 / self.value)
+    }
+
+    pub fn relu(self) -> Dual {
+        if self.value > 0.0 {
+            self
+        } else {
+            Dual::constant(0.0)
+        }
+    }
+
+    pub fn sigmoid(self) -> Dual {
+        let s = 1.0 / (1.0 + (-self.value).exp());
+        self.chain(s, s * (1.0 - s))
+    }
+
+    pub fn tanh(self) -> Dual {
+        let t = self.value.tanh();
+        self.chain(t, 1.0 - t * t)
+    }
+}
+
+impl From<f32> for Dual {
+    fn from(value: f32) -> Self {
+        Dual::constant(value)
+    }
+}
+
+impl std::ops::Add for Dual {
+    type Output = Dual;
+    fn add(self, rhs: Dual) -> Dual {
+        Dual::new(self.value + rhs.value, self.derivative + rhs.derivative)
+    }
+}
+
+impl std::ops::Sub for Dual {
+    type Output = Dual;
+    fn sub(self, rhs: Dual) -> Dual {
+        Dual::new(self.value - rhs.value, self.derivative - rhs.derivative)
+    }
+}
+
+impl std::ops::Mul for Dual {
+    type Output = Dual;
+    fn mul(self, rhs: Dual) -> Dual {
+        Dual::new(
+            self.value * rhs.value,
+            self.derivative * rhs.value + self.value * rhs.derivative,
+        )
+    }
+}
+
+impl std::ops::Div for Dual {
+    type Output = Dual;
+    fn div(self, rhs: Dual) -> Dual {
+        Dual::new(
+            self.value / rhs.value,
+            (self.derivative * rhs.value - self.value * rhs.derivative) / (rhs.value * rhs.value),
+        )
+    }
+}
+
+impl std::ops::Neg for Dual {
+    type Output = Dual;
+    fn neg(self) -> Dual {
+        Dual::new(-self.value, -self.derivative)
+    }
+}
+
+/// `f32` on either side of a `Dual op Dual` impl, as a constant.
+macro_rules! dual_scalar_op {
+    ($trait:ident, $method:ident) => {
+        impl std::ops::$trait<f32> for Dual {
+            type Output = Dual;
+            fn $method(self, rhs: f32) -> Dual {
+                self.$method(Dual::constant(rhs))
+            }
+        }
+
+        impl std::ops::$trait<Dual> for f32 {
+            type Output = Dual;
+            fn $method(self, rhs: Dual) -> Dual {
+                Dual::constant(self).$method(rhs)
+            }
+        }
+    };
+}
+
+dual_scalar_op!(Add, add);
+dual_scalar_op!(Sub, sub);
+dual_scalar_op!(Mul, mul);
+dual_scalar_op!(Div, div);
+
+impl std::iter::Sum for Dual {
+    fn sum<I: Iterator<Item = Dual>>(iter: I) -> Dual {
+        iter.fold(Dual::constant(0.0), |acc, d| acc + d)
+    }
+}
+
+/// Gradient of `f` at `inputs`, one forward pass per input.
+pub fn gradient(f: impl Fn(&[Dual]) -> Dual, inputs: &[f32]) -> Vec<f32> {
+    (0..inputs.len())
+        .map(|i| {
+            let duals: Vec<Dual> = inputs
+                .iter()
+                .enumerate()
+                .map(|(j, &x)| Dual::new(x, if i == j { 1.0 } else { 0.0 }))
+                .collect();
+            f(&duals).derivative
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng, rngs::StdRng};
+
+    use super::*;
+    use crate::Value;
+
+    /// An expression tree evaluated both as `Value`s and as `Dual`s. Ops with a
+    /// restricted domain get safe inputs: `log(e^2 + 1)`, `a / (b^2 + 1)` and
+    /// `exp(tanh(e))`.
+    #[derive(Debug)]
+    enum Expr {
+        Variable(usize),
+        Constant(f32),
+        Add(Box<Expr>, Box<Expr>),
+        Sub(Box<Expr>, Box<Expr>),
+        Mul(Box<Expr>, Box<Expr>),
+        Div(Box<Expr>, Box<Expr>),
+        Neg(Box<Expr>),
+        Pow(Box<Expr>, f32),
+        Exp(Box<Expr>),
+        Log(Box<Expr>),
+        Relu(Box<Expr>),
+        Sigmoid(Box<Expr>),
+        Tanh(Box<Expr>),
+    }
+
+    fn random_expr(rng: &mut StdRng, depth: u32, variables: usize) -> Expr {
+        if depth == 0 || rng.random_bool(0.2) {
+            return if rng.random_bool(0.7) {
+                Expr::Variable(rng.random_range(0..variables))
+            } else {
+                Expr::Constant(rng.random_range(-2.0..2.0))
+            };
+        }
+        let mut sub = || Box::new(random_expr(rng, depth - 1, variables));
+        let (a, b) = (sub(), sub());
+        match rng.random_range(0..12) {
+            0 => Expr::Add(a, b),
+            1 => Expr::Sub(a, b),
+            2 => Expr::Mul(a, b),
+            3 => Expr::Div(a, b),
+            4 => Expr::Neg(a),
+            5 => Expr::Pow(a, 2.0),
+            6 => Expr::Pow(a, 3.0),
+            7 => Expr::Exp(a),
+            8 => Expr::Log(a),
+            9 => Expr::Relu(a),
+            10 => Expr::Sigmoid(a),
+            _ => Expr::Tanh(a),
+        }
+    }
+
+    fn eval_value(expr: &Expr, inputs: &[Value]) -> Value {
+        let eval = |e: &Expr| eval_value(e, inputs);
+        match expr {
+            Expr::Variable(i) => inputs[*i].clone(),
+            Expr::Constant(c) => Value::new(*c),
+            Expr::Add(a, b) => eval(a) + eval(b),
+            Expr::Sub(a, b) => eval(a) - eval(b),
+            Expr::Mul(a, b) => eval(a) * eval(b),
+            Expr::Div(a, b) => eval(a) / (eval(b).pow(2.0) + 1.0),
+            Expr::Neg(a) => -eval(a),
+            Expr::Pow(a, exponent) => eval(a).pow(*exponent),
+            Expr::Exp(a) => eval(a).tanh().exp(),
+            Expr::Log(a) => (eval(a).pow(2.0) + 1.0).log(),
+            Expr::Relu(a) => eval(a).relu(),
+            Expr::Sigmoid(a) => eval(a).sigmoid(),
+            Expr::Tanh(a) => eval(a).tanh(),
+        }
+    }
+
+    fn eval_dual(expr: &Expr, inputs: &[Dual]) -> Dual {
+        let eval = |e: &Expr| eval_dual(e, inputs);
+        match expr {
+            Expr::Variable(i) => inputs[*i],
+            Expr::Constant(c) => Dual::constant(*c),
+            Expr::Add(a, b) => eval(a) + eval(b),
+            Expr::Sub(a, b) => eval(a) - eval(b),
+            Expr::Mul(a, b) => eval(a) * eval(b),
+            Expr::Div(a, b) => eval(a) / (eval(b).pow(2.0) + 1.0),
+            Expr::Neg(a) => -eval(a),
+            Expr::Pow(a, exponent) => eval(a).pow(*exponent),
+            Expr::Exp(a) => eval(a).tanh().exp(),
+            Expr::Log(a) => (eval(a).pow(2.0) + 1.0).log(),
+            Expr::Relu(a) => eval(a).relu(),
+            Expr::Sigmoid(a) => eval(a).sigmoid(),
+            Expr::Tanh(a) => eval(a).tanh(),
+        }
+    }
+
+    fn assert_close(forward: f32, reverse: f32, what: &str) {
+        assert!(
+            (forward - reverse).abs() <= 1e-3 * (1.0 + forward.abs().max(reverse.abs())),
+            "{what}: forward {forward} reverse {reverse}"
+        );
+    }
+
+    #[test]
+    fn elementary_derivatives() {
+        let x = Dual::variable(0.5);
+
+        assert_eq!((x * x + 1.0).derivative, 1.0);
+        assert_eq!((1.0 / x).derivative, -4.0);
+        assert_eq!(x.pow(3.0).derivative, 0.75);
+        assert_eq!(x.log().derivative, 2.0);
+        assert_eq!((-x).relu(), Dual::constant(0.0));
+        assert_eq!(Dual::constant(3.0).tanh().derivative, 0.0);
+        assert_eq!(gradient(|v| v[0] * v[1] - v[1], &[2.0, 3.0]), [3.0, 1.0]);
+    }
+
+    #[test]
+    fn forward_matches_reverse_on_random_expressions() {
+        let mut rng = StdRng::seed_from_u64(50);
+        for case in 0..300 {
+            let variables = rng.random_range(1..4);
+            let expr = random_expr(&mut rng, 5, variables);
+            let inputs: Vec<f32> = (0..variables)
+                .map(|_| rng.random_range(-1.5..1.5))
+                .collect();
+
+            let values: Vec<Value> = inputs.iter().map(|&x| Value::new(x)).collect();
+            let output = eval_value(&expr, &values);
+            if !output.get_data().is_finite() {
+                continue;
+            }
+            output.backward();
+            let forward = gradient(|duals| eval_dual(&expr, duals), &inputs);
+
+            let what = format!("case {case}: {expr:?} at {inputs:?}");
+            assert_close(
+                eval_dual(
+                    &expr,
+                    &inputs
+                        .iter()
+                        .map(|&x| Dual::constant(x))
+                        .collect::<Vec<_>>(),
+                )
+                .value,
+                output.get_data(),
+                &what,
+            );
+            for (value, forward) in values.iter().zip(forward) {
+                assert_close(forward, value.get_grad(), &what);
+            }
+        }
+    }
+}
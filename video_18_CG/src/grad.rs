@@ -0,0 +1,180 @@
+use std::collections::HashMap;
+
+use crate::{Op, Value, topological_order};
+
+/// `d root / d x` for every `x` in `wrt`, without touching the `grad` fields.
+///
+/// The backward pass is built from `Value` ops on top of the graph of `root`. With
+/// `create_graph` the returned gradients keep that graph, so they can be
+/// differentiated again; otherwise they are detached leaves.
+pub fn grad(root: &Value, wrt: &[Value], create_graph: bool) -> Vec<Value> {
+    let mut grads: HashMap<usize, Value> = HashMap::new();
+    grads.insert(root.id, Value::new(1.0));
+
+    for v in topological_order(&[root]).iter().rev() {
+        let Some(g) = grads.get(&v.id).cloned() else {
+            continue;
+        };
+        let mut send = |operand: &Value, contribution: Value| {
+            let sum = match grads.remove(&operand.id) {
+                Some(existing) => existing + contribution,
+                None => contribution,
+            };
+            grads.insert(operand.id, sum);
+        };
+        match &v.op {
+            Op::None => {}
+            Op::Add(a, b) => {
+                send(a, g.clone());
+                send(b, g);
+            }
+            Op::Sub(a, b) => {
+                send(a, g.clone());
+                send(b, -g);
+            }
+            Op::Mul(a, b) => {
+                send(a, &g * b);
+                send(b, g * a);
+            }
+            Op::Div(a, b) => {
+                send(a, &g / b);
+                send(b, -(g * a) / (b * b));
+            }
+            Op::Neg(a) => send(a, -g),
+            Op::Pow(a, exponent) => send(a, g * *exponent * a.pow(exponent - 1.0)),
+            Op::Exp(a) => send(a, g * v),
+            Op::Log(a) => send(a, g / a),
+            // The derivative of the step is zero almost everywhere.
+            Op::Relu(a) => {
+                let step = if v.get_data() > 0.0 { 1.0 } else { 0.0 };
+                send(a, g * step)
+            }
+            Op::Sigmoid(a) => send(a, g * v * (1.0 - v)),
+            Op::Tanh(a) => send(a, g * (1.0 - v * v)),
+        }
+    }
+
+    wrt.iter()
+        .map(|x| match grads.get(&x.id) {
+            Some(g) if create_graph => g.clone(),
+            Some(g) => Value::new(g.get_data()),
+            None => Value::new(0.0),
+        })
+        .collect()
+}
+
+/// `jacobian[i][j] = d outputs[i] / d wrt[j]`.
+pub fn jacobian(outputs: &[Value], wrt: &[Value]) -> Vec<Vec<f32>> {
+    outputs
+        .iter()
+        .map(|output| {
+            grad(output, wrt, false)
+                .iter()
+                .map(|g| g.get_data())
+                .collect()
+        })
+        .collect()
+}
+
+/// `hessian[i][j] = d^2 output / (d wrt[i] d wrt[j])`.
+pub fn hessian(output: &Value, wrt: &[Value]) -> Vec<Vec<f32>> {
+    let first = grad(output, wrt, true);
+    jacobian(&first, wrt)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() < 1e-4 * (1.0 + expected.abs()),
+            "{actual} != {expected}"
+        );
+    }
+
+    #[test]
+    fn second_derivatives() {
+        let x = Value::new(0.7);
+        let cube = x.pow(3.0);
+        let t = x.tanh();
+        let wrt = [x];
+
+        let [d_cube] = grad(&cube, &wrt, true).try_into().unwrap();
+        let [dd_cube] = grad(&d_cube, &wrt, false).try_into().unwrap();
+        let [d_t] = grad(&t, &wrt, true).try_into().unwrap();
+        let [dd_t] = grad(&d_t, &wrt, true).try_into().unwrap();
+        let [ddd_t] = grad(&dd_t, &wrt, false).try_into().unwrap();
+
+        let th = 0.7f32.tanh();
+        assert_close(d_cube.get_data(), 3.0 * 0.49);
+        assert_close(dd_cube.get_data(), 6.0 * 0.7);
+        assert_close(dd_t.get_data(), -2.0 * th * (1.0 - th * th));
+        assert_close(
+            ddd_t.get_data(),
+            -2.0 * (1.0 - th * th) * (1.0 - 3.0 * th * th),
+        );
+    }
+
+    #[test]
+    fn detached_without_create_graph() {
+        let x = Value::new(0.5);
+        let y = Value::new(2.0);
+        let f = &x * &x * &y;
+
+        let wrt = [x, y];
+        let grads = grad(&f, &wrt, false);
+
+        assert_close(grads[0].get_data(), 2.0);
+        assert_close(grads[1].get_data(), 0.25);
+        assert_eq!(grad(&grads[0], &wrt, false)[0].get_data(), 0.0);
+        assert!(wrt.iter().all(|v| v.get_grad() == 0.0));
+    }
+
+    #[test]
+    fn jacobian_matches_analytic() {
+        let (a, b) = (0.3, -1.2);
+        let x = Value::new(a);
+        let y = Value::new(b);
+        let unrelated = Value::new(1.0);
+        let outputs = [&x * &y, &x / &y, x.tanh()];
+
+        let j = jacobian(&outputs, &[x.clone(), y.clone(), unrelated]);
+
+        let expected = [
+            [b, a, 0.0],
+            [1.0 / b, -a / (b * b), 0.0],
+            [1.0 - a.tanh().powi(2), 0.0, 0.0],
+        ];
+        for (row, expected) in j.iter().zip(expected) {
+            for (&actual, expected) in row.iter().zip(expected) {
+                assert_close(actual, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn hessian_matches_analytic() {
+        let (a, b) = (0.4, -0.6);
+        let x = Value::new(a);
+        let y = Value::new(b);
+        // f = x^2 y + exp(x y) + log(x)
+        let f = x.pow(2.0) * &y + (&x * &y).exp() + x.log();
+
+        let h = hessian(&f, &[x, y]);
+
+        let e = (a * b).exp();
+        let expected = [
+            [
+                2.0 * b + b * b * e - 1.0 / (a * a),
+                2.0 * a + e * (1.0 + a * b),
+            ],
+            [2.0 * a + e * (1.0 + a * b), a * a * e],
+        ];
+        for (row, expected) in h.iter().zip(expected) {
+            for (&actual, expected) in row.iter().zip(expected) {
+                assert_close(actual, expected);
+            }
+        }
+    }
+}
@@ -1,14 +1,26 @@
+#![allow(non_snake_case)]
+
 use dot_generator::{attr, node};
 use dot_generator::{edge, id, node_id};
 use dot_structures::*;
 
-use std::{
-    cell::RefCell,
-    collections::{HashSet, LinkedList},
-    ops::Deref,
-    rc::Rc,
-    sync::atomic::AtomicUsize,
-};
+mod dot;
+mod dual;
+mod grad;
+mod nn;
+mod parse;
+mod tape;
+mod tensor;
+
+pub use dot::*;
+pub use dual::*;
+pub use grad::*;
+pub use nn::*;
+pub use parse::*;
+pub use tape::*;
+pub use tensor::*;
+
+use std::{cell::RefCell, collections::HashSet, ops::Deref, rc::Rc, sync::atomic::AtomicUsize};
 
 #[derive(Debug, Clone)]
 pub struct Value(Rc<Value_>);
@@ -18,29 +30,35 @@ pub struct Value_ {
     id: usize,
     data: RefCell<f32>,
     grad: RefCell<f32>,
+    name: RefCell<Option<String>>,
     op: Op,
 }
 
-fn topological_order(root: &Value) -> LinkedList<Value> {
-    let mut order = LinkedList::new();
+/// Every node reachable from `roots`, each one after its operands.
+fn topological_order(roots: &[&Value]) -> Vec<Value> {
+    let mut order = Vec::new();
     let mut visited = HashSet::new();
-    fn depth_first(value: &Value, visited: &mut HashSet<usize>, order: &mut LinkedList<Value>) {
-        if !visited.contains(&value.id) {
-            visited.insert(value.id);
-            match &value.op {
-                Op::None => {}
-                Op::Add(lhs, rhs) | Op::Sub(lhs, rhs) | Op::Mul(lhs, rhs) => {
-                    depth_first(lhs, visited, order);
-                    depth_first(rhs, visited, order);
-                }
-                Op::Tanh(inner) => {
-                    depth_first(inner, visited, order);
-                }
-            }
-            order.push_front(value.clone());
+    // `true` once the operands of the value are on the stack above it.
+    let mut stack: Vec<(Value, bool)> = roots.iter().rev().map(|&r| (r.clone(), false)).collect();
+    while let Some((value, expanded)) = stack.pop() {
+        if expanded {
+            order.push(value);
+            continue;
+        }
+        if !visited.insert(value.id) {
+            continue;
         }
+        let operands: Vec<Value> = value
+            .op
+            .operands()
+            .into_iter()
+            .rev()
+            .filter(|operand| !visited.contains(&operand.id))
+            .cloned()
+            .collect();
+        stack.push((value, true));
+        stack.extend(operands.into_iter().map(|operand| (operand, false)));
     }
-    depth_first(root, &mut visited, &mut order);
     order
 }
 
@@ -49,14 +67,86 @@ impl Value {
         Value(Rc::new(Value_::new(data)))
     }
 
-    pub fn tanh(&self) -> Value {
-        let d = self.get_data().tanh();
-        let mut v = Value_::new(d);
-        let op = Op::Tanh((*self).clone());
+    pub fn named(data: f32, name: impl Into<String>) -> Self {
+        let v = Value::new(data);
+        v.set_name(name);
+        v
+    }
+
+    fn with_op(data: f32, op: Op) -> Value {
+        let mut v = Value_::new(data);
         v.op = op;
         Value(Rc::new(v))
     }
+
+    pub fn tanh(&self) -> Value {
+        Value::with_op(self.get_data().tanh(), Op::Tanh(self.clone()))
+    }
+
+    pub fn pow(&self, exponent: f32) -> Value {
+        Value::with_op(
+            self.get_data().powf(exponent),
+            Op::Pow(self.clone(), exponent),
+        )
+    }
+
+    pub fn exp(&self) -> Value {
+        Value::with_op(self.get_data().exp(), Op::Exp(self.clone()))
+    }
+
+    /// Natural logarithm.
+    pub fn log(&self) -> Value {
+        Value::with_op(self.get_data().ln(), Op::Log(self.clone()))
+    }
+
+    pub fn relu(&self) -> Value {
+        Value::with_op(self.get_data().max(0.0), Op::Relu(self.clone()))
+    }
+
+    pub fn sigmoid(&self) -> Value {
+        let d = 1.0 / (1.0 + (-self.get_data()).exp());
+        Value::with_op(d, Op::Sigmoid(self.clone()))
+    }
+
+    /// Resets the grad of every node in the graph of `self`.
+    pub fn zero_grad(&self) {
+        for v in topological_order(&[self]) {
+            v.set_grad(0.0);
+        }
+    }
+
+    /// Replaces the grads in the graph of `self` with `d self / d node`.
+    pub fn backward(&self) {
+        backward(&[(self, 1.0)]);
+    }
+
+    /// Recomputes `data` of every node from the current leaf data, e.g. after
+    /// [`Value_::set_data`] on an input, and returns the new data of `self`.
+    pub fn forward(&self) -> f32 {
+        for v in &topological_order(&[self]) {
+            if let Some(d) = v.op.compute() {
+                v.set_data(d);
+            }
+        }
+        self.get_data()
+    }
+}
+impl Drop for Value {
+    // Dropping the last handle of a deep graph would recurse once per node, so the
+    // operands owned only by this graph are unlinked one by one instead.
+    fn drop(&mut self) {
+        let mut pending = Vec::new();
+        if let Some(node) = Rc::get_mut(&mut self.0) {
+            pending.extend(std::mem::replace(&mut node.op, Op::None).into_operands());
+        }
+        while let Some(mut value) = pending.pop() {
+            if let Some(node) = Rc::get_mut(&mut value.0) {
+                pending.extend(std::mem::replace(&mut node.op, Op::None).into_operands());
+            }
+        }
+    }
 }
+
 impl Deref for Value {
     type Target = Value_;
 
@@ -76,6 +166,7 @@ impl Value_ {
             id: get_id(),
             data: RefCell::new(data),
             grad: RefCell::new(0.0),
+            name: RefCell::new(None),
             op: Op::None,
         }
     }
@@ -86,6 +177,23 @@ impl Value_ {
     pub fn get_grad(&self) -> f32 {
         *self.grad.borrow()
     }
+
+    pub fn set_data(&self, data: f32) {
+        *self.data.borrow_mut() = data;
+    }
+
+    pub fn set_grad(&self, grad: f32) {
+        *self.grad.borrow_mut() = grad;
+    }
+
+    /// Optional label, shown by [`to_dot`].
+    pub fn name(&self) -> Option<String> {
+        self.name.borrow().clone()
+    }
+
+    pub fn set_name(&self, name: impl Into<String>) {
+        *self.name.borrow_mut() = Some(name.into());
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -94,9 +202,70 @@ enum Op {
     Add(Value, Value),
     Sub(Value, Value),
     Mul(Value, Value),
+    Div(Value, Value),
+    Neg(Value),
+    /// Raised to a constant exponent.
+    Pow(Value, f32),
+    Exp(Value),
+    Log(Value),
+    Relu(Value),
+    Sigmoid(Value),
     Tanh(Value),
 }
 
+impl Op {
+    /// Data of a node with this op from the current operand data, `None` for leaves.
+    fn compute(&self) -> Option<f32> {
+        let d = match self {
+            Op::None => return None,
+            Op::Add(lhs, rhs) => lhs.get_data() + rhs.get_data(),
+            Op::Sub(lhs, rhs) => lhs.get_data() - rhs.get_data(),
+            Op::Mul(lhs, rhs) => lhs.get_data() * rhs.get_data(),
+            Op::Div(lhs, rhs) => lhs.get_data() / rhs.get_data(),
+            Op::Neg(inner) => -inner.get_data(),
+            Op::Pow(inner, exponent) => inner.get_data().powf(*exponent),
+            Op::Exp(inner) => inner.get_data().exp(),
+            Op::Log(inner) => inner.get_data().ln(),
+            Op::Relu(inner) => inner.get_data().max(0.0),
+            Op::Sigmoid(inner) => 1.0 / (1.0 + (-inner.get_data()).exp()),
+            Op::Tanh(inner) => inner.get_data().tanh(),
+        };
+        Some(d)
+    }
+
+    fn into_operands(self) -> Vec<Value> {
+        match self {
+            Op::None => vec![],
+            Op::Add(lhs, rhs) | Op::Sub(lhs, rhs) | Op::Mul(lhs, rhs) | Op::Div(lhs, rhs) => {
+                vec![lhs, rhs]
+            }
+            Op::Neg(inner)
+            | Op::Pow(inner, _)
+            | Op::Exp(inner)
+            | Op::Log(inner)
+            | Op::Relu(inner)
+            | Op::Sigmoid(inner)
+            | Op::Tanh(inner) => vec![inner],
+        }
+    }
+
+    fn operands(&self) -> Vec<&Value> {
+        match self {
+            Op::None => vec![],
+            Op::Add(lhs, rhs) | Op::Sub(lhs, rhs) | Op::Mul(lhs, rhs) | Op::Div(lhs, rhs) => {
+                vec![lhs, rhs]
+            }
+            Op::Neg(inner)
+            | Op::Pow(inner, _)
+            | Op::Exp(inner)
+            | Op::Log(inner)
+            | Op::Relu(inner)
+            | Op::Sigmoid(inner)
+            | Op::Tanh(inner) => vec![inner],
+        }
+    }
+}
+
 impl std::fmt::Display for Op {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let name = match self {
@@ -104,6 +273,13 @@ impl std::fmt::Display for Op {
             Op::Add(_, _) => "Add",
             Op::Sub(_, _) => "Sub",
             Op::Mul(_, _) => "Mul",
+            Op::Div(_, _) => "Div",
+            Op::Neg(_) => "Neg",
+            Op::Pow(_, _) => "Pow",
+            Op::Exp(_) => "Exp",
+            Op::Log(_) => "Log",
+            Op::Relu(_) => "Relu",
+            Op::Sigmoid(_) => "Sigmoid",
             Op::Tanh(_) => "Tanh",
         };
         write!(f, "{name}",)
@@ -114,10 +290,7 @@ impl std::ops::Add for &Value {
     type Output = Value;
     fn add(self, rhs: Self) -> Self::Output {
         let d = self.get_data() + rhs.get_data();
-        let mut v = Value_::new(d);
-        let op = Op::Add((*self).clone(), (*rhs).clone());
-        v.op = op;
-        Value(Rc::new(v))
+        Value::with_op(d, Op::Add(self.clone(), rhs.clone()))
     }
 }
 
@@ -125,10 +298,7 @@ impl std::ops::Sub for &Value {
     type Output = Value;
     fn sub(self, rhs: Self) -> Self::Output {
         let d = self.get_data() - rhs.get_data();
-        let mut v = Value_::new(d);
-        let op = Op::Sub((*self).clone(), (*rhs).clone());
-        v.op = op;
-        Value(Rc::new(v))
+        Value::with_op(d, Op::Sub(self.clone(), rhs.clone()))
     }
 }
 
@@ -137,15 +307,111 @@ impl std::ops::Mul for &Value {
 
     fn mul(self, rhs: Self) -> Self::Output {
         let d = self.get_data() * rhs.get_data();
-        let mut v = Value_::new(d);
-        let op = Op::Mul((*self).clone(), (*rhs).clone());
-        v.op = op;
-        Value(Rc::new(v))
+        Value::with_op(d, Op::Mul(self.clone(), rhs.clone()))
+    }
+}
+
+impl std::ops::Div for &Value {
+    type Output = Value;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        let d = self.get_data() / rhs.get_data();
+        Value::with_op(d, Op::Div(self.clone(), rhs.clone()))
+    }
+}
+
+impl std::ops::Neg for &Value {
+    type Output = Value;
+
+    fn neg(self) -> Self::Output {
+        Value::with_op(-self.get_data(), Op::Neg(self.clone()))
+    }
+}
+
+impl std::ops::Neg for Value {
+    type Output = Value;
+
+    fn neg(self) -> Self::Output {
+        -&self
+    }
+}
+
+/// Owned and `f32` operands for a `&T op &T` impl; scalars become leaves through `From<f32>`.
+macro_rules! forward_binary_op {
+    ($type:ident, $trait:ident, $method:ident) => {
+        impl std::ops::$trait<$type> for $type {
+            type Output = $type;
+            fn $method(self, rhs: $type) -> $type {
+                (&self).$method(&rhs)
+            }
+        }
+
+        impl std::ops::$trait<&$type> for $type {
+            type Output = $type;
+            fn $method(self, rhs: &$type) -> $type {
+                (&self).$method(rhs)
+            }
+        }
+
+        impl std::ops::$trait<$type> for &$type {
+            type Output = $type;
+            fn $method(self, rhs: $type) -> $type {
+                self.$method(&rhs)
+            }
+        }
+
+        impl std::ops::$trait<f32> for &$type {
+            type Output = $type;
+            fn $method(self, rhs: f32) -> $type {
+                self.$method(&$type::from(rhs))
+            }
+        }
+
+        impl std::ops::$trait<f32> for $type {
+            type Output = $type;
+            fn $method(self, rhs: f32) -> $type {
+                (&self).$method(&$type::from(rhs))
+            }
+        }
+
+        impl std::ops::$trait<&$type> for f32 {
+            type Output = $type;
+            fn $method(self, rhs: &$type) -> $type {
+                (&$type::from(self)).$method(rhs)
+            }
+        }
+
+        impl std::ops::$trait<$type> for f32 {
+            type Output = $type;
+            fn $method(self, rhs: $type) -> $type {
+                (&$type::from(self)).$method(&rhs)
+            }
+        }
+    };
+}
+
+pub(crate) use forward_binary_op;
+
+forward_binary_op!(Value, Add, add);
+forward_binary_op!(Value, Sub, sub);
+forward_binary_op!(Value, Mul, mul);
+forward_binary_op!(Value, Div, div);
+
+impl From<f32> for Value {
+    fn from(data: f32) -> Self {
+        Value::new(data)
+    }
+}
+
+impl std::iter::Sum for Value {
+    fn sum<I: Iterator<Item = Value>>(iter: I) -> Value {
+        iter.reduce(|acc, v| acc + v)
+            .unwrap_or_else(|| Value::new(0.0))
     }
 }
 
 pub fn viz_computation_graph(value: &Value, graph: &mut Graph) {
-    let tp_order = topological_order(value);
+    let tp_order = topological_order(&[value]);
     for value in &tp_order {
         let value_node_id = value.id;
         let value_node = node!(
@@ -161,23 +427,43 @@ pub fn viz_computation_graph(value: &Value, graph: &mut Graph) {
             let e = edge!(node_id!(p_node_id) => node_id!(value_node_id));
             graph.add_stmt(e.into());
         };
-        match &value.op {
-            Op::Add(lhs, rhs) | Op::Sub(lhs, rhs) | Op::Mul(lhs, rhs) => {
-                add_edge(lhs);
-                add_edge(rhs);
-            }
-            Op::Tanh(inner) => {
-                add_edge(inner);
-            }
-            Op::None => {}
+        for operand in value.op.operands() {
+            add_edge(operand);
         }
     }
 }
 
+/// Accumulates `d root / d leaf` into the grads of the leaves, so repeated calls
+/// sum up until [`Value::zero_grad`]. Inner nodes are recomputed on every call.
 pub fn calculat_grad(root: &Value) {
+    let tp_order = topological_order(&[root]);
+    for v in &tp_order {
+        if !matches!(v.op, Op::None) {
+            v.set_grad(0.0);
+        }
+    }
     *root.0.grad.borrow_mut() = 1.0;
-    let tp_order = topological_order(root);
-    for v in tp_order {
+    propagate(&tp_order);
+}
+
+/// Resets the graph of all `roots`, then back-propagates each root seeded with its
+/// upstream gradient: the grads become those of `sum(seed * root)`.
+///
+/// A seed other than 1.0 continues a backward pass that started outside the graph.
+pub fn backward(roots: &[(&Value, f32)]) {
+    let values: Vec<&Value> = roots.iter().map(|(root, _)| *root).collect();
+    let tp_order = topological_order(&values);
+    for v in &tp_order {
+        v.set_grad(0.0);
+    }
+    for (root, seed) in roots {
+        *root.grad.borrow_mut() += seed;
+    }
+    propagate(&tp_order);
+}
+
+fn propagate(tp_order: &[Value]) {
+    for v in tp_order.iter().rev() {
         match &v.op {
             Op::None => {}
             Op::Add(v1, v2) => {
@@ -198,6 +484,33 @@ pub fn calculat_grad(root: &Value) {
                 *v1.grad.borrow_mut() += v.get_grad();
                 *v2.grad.borrow_mut() += -v.get_grad();
             }
+            Op::Div(v1, v2) => {
+                let d2 = v2.get_data();
+                *v1.grad.borrow_mut() += v.get_grad() / d2;
+                *v2.grad.borrow_mut() += -v.get_grad() * v1.get_data() / (d2 * d2);
+            }
+            Op::Neg(v1) => {
+                *v1.grad.borrow_mut() += -v.get_grad();
+            }
+            Op::Pow(v1, exponent) => {
+                let local_grad = exponent * v1.get_data().powf(exponent - 1.0);
+                *v1.grad.borrow_mut() += v.get_grad() * local_grad;
+            }
+            Op::Exp(v1) => {
+                *v1.grad.borrow_mut() += v.get_grad() * v.get_data();
+            }
+            Op::Log(v1) => {
+                *v1.grad.borrow_mut() += v.get_grad() / v1.get_data();
+            }
+            Op::Relu(v1) => {
+                if v.get_data() > 0.0 {
+                    *v1.grad.borrow_mut() += v.get_grad();
+                }
+            }
+            Op::Sigmoid(v1) => {
+                let s = v.get_data();
+                *v1.grad.borrow_mut() += v.get_grad() * s * (1.0 - s);
+            }
         }
     }
 }
@@ -206,8 +519,6 @@ pub fn calculat_grad(root: &Value) {
 mod tests {
     use super::*;
     use dot_generator::graph;
-    use graphviz_rust::cmd::CommandArg;
-    use graphviz_rust::{cmd::Format, exec, printer::PrinterContext};
 
     #[test]
     pub fn it_works() {
@@ -221,11 +532,243 @@ mod tests {
         calculat_grad(v6);
         let mut graph = graph!(id!("copm"));
         viz_computation_graph(v6, &mut graph);
-        let _graph_svg = exec(
-            graph,
-            &mut PrinterContext::default(),
-            vec![Format::Png.into(), CommandArg::Output("./1.png".into())],
-        )
-        .unwrap();
+
+        let Graph::Graph { stmts, .. } = graph else {
+            unreachable!()
+        };
+        let nodes = stmts.iter().filter(|s| matches!(s, Stmt::Node(_))).count();
+        let edges = stmts.iter().filter(|s| matches!(s, Stmt::Edge(_))).count();
+        assert_eq!((nodes, edges), (6, 7));
+    }
+
+    const EPS: f32 = 1e-3;
+
+    /// Compares the gradients from `calculat_grad` against central differences.
+    fn check_gradients(f: impl Fn(&[Value]) -> Value, inputs: &[f32]) {
+        let values: Vec<Value> = inputs.iter().map(|&x| Value::new(x)).collect();
+        calculat_grad(&f(&values));
+
+        for (i, value) in values.iter().enumerate() {
+            let eval = |delta: f32| {
+                let mut shifted = inputs.to_vec();
+                shifted[i] += delta;
+                let shifted: Vec<Value> = shifted.into_iter().map(Value::new).collect();
+                f(&shifted).get_data()
+            };
+            let numeric = (eval(EPS) - eval(-EPS)) / (2.0 * EPS);
+            let analytic = value.get_grad();
+
+            assert!(
+                (numeric - analytic).abs() < 1e-2 * (1.0 + numeric.abs()),
+                "input {i}: numeric {numeric} analytic {analytic}"
+            );
+        }
+    }
+
+    #[test]
+    fn add_gradient() {
+        check_gradients(|v| &v[0] + &v[1], &[0.5, -1.5]);
+        check_gradients(|v| 2.0 + v[0].clone() + 1.0, &[0.3]);
+    }
+
+    #[test]
+    fn sub_gradient() {
+        check_gradients(|v| &v[0] - &v[1], &[0.5, -1.5]);
+        check_gradients(|v| 1.0 - &v[0] - 2.0, &[0.3]);
+    }
+
+    #[test]
+    fn mul_gradient() {
+        check_gradients(|v| &v[0] * &v[1], &[0.5, -1.5]);
+        check_gradients(|v| 3.0 * &v[0] * &v[0], &[0.7]);
+    }
+
+    #[test]
+    fn div_gradient() {
+        check_gradients(|v| &v[0] / &v[1], &[0.5, -1.5]);
+        check_gradients(|v| 1.0 / &v[0] + &v[0] / 4.0, &[0.8]);
+    }
+
+    #[test]
+    fn neg_gradient() {
+        check_gradients(|v| -(&v[0] * &v[1]), &[0.5, -1.5]);
+    }
+
+    #[test]
+    fn pow_gradient() {
+        check_gradients(|v| v[0].pow(3.0), &[1.2]);
+        check_gradients(|v| v[0].pow(-0.5), &[2.0]);
+    }
+
+    #[test]
+    fn exp_gradient() {
+        check_gradients(|v| (&v[0] * &v[1]).exp(), &[0.5, -1.5]);
+    }
+
+    #[test]
+    fn log_gradient() {
+        check_gradients(|v| (&v[0] * &v[0] + 1.0).log(), &[0.6]);
+    }
+
+    #[test]
+    fn relu_gradient() {
+        check_gradients(|v| (&v[0] * 2.0).relu(), &[0.4]);
+        check_gradients(|v| (&v[0] * 2.0).relu(), &[-0.4]);
+    }
+
+    #[test]
+    fn sigmoid_gradient() {
+        check_gradients(|v| (&v[0] - &v[1]).sigmoid(), &[0.5, -1.5]);
+    }
+
+    #[test]
+    fn tanh_gradient() {
+        check_gradients(|v| (&v[0] * &v[1]).tanh(), &[0.5, -0.8]);
+    }
+
+    #[test]
+    fn composite_gradient() {
+        check_gradients(
+            |v| {
+                let a = (&v[0] * &v[1] + v[2].exp()).tanh();
+                let b = (&v[1] / 2.0 - &v[2]).sigmoid().log();
+                a * b - v[0].pow(2.0).relu()
+            },
+            &[0.4, -0.7, 0.2],
+        );
+    }
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!((actual - expected).abs() < 1e-5, "{actual} != {expected}");
+    }
+
+    #[test]
+    fn backward_twice_gives_same_grads() {
+        let x = Value::new(0.5);
+        let y = Value::new(-1.5);
+        let z = (&x * &y + &x).tanh();
+
+        z.backward();
+        let first = (x.get_grad(), y.get_grad());
+        z.backward();
+
+        assert_eq!((x.get_grad(), y.get_grad()), first);
+    }
+
+    #[test]
+    fn calculat_grad_accumulates_leaves_only() {
+        let x = Value::new(0.5);
+        let z = (&x * &x).tanh();
+
+        calculat_grad(&z);
+        let once = x.get_grad();
+        calculat_grad(&z);
+
+        assert_close(x.get_grad(), 2.0 * once);
+        assert_eq!(z.get_grad(), 1.0);
+    }
+
+    #[test]
+    fn zero_grad_resets_graph() {
+        let x = Value::new(0.5);
+        let y = &x * 3.0;
+        let z = y.exp();
+        calculat_grad(&z);
+
+        z.zero_grad();
+
+        assert!([&x, &y, &z].iter().all(|v| v.get_grad() == 0.0));
+    }
+
+    #[test]
+    fn backward_multiple_roots() {
+        let x = Value::new(0.5);
+        let y = Value::new(-1.5);
+        let shared = &x * &y;
+        let a = shared.tanh();
+        let b = &shared + &x;
+
+        backward(&[(&a, 1.0), (&b, 0.5)]);
+        let grads = (x.get_grad(), y.get_grad());
+        (a.clone() + 0.5 * b.clone()).backward();
+
+        assert_close(grads.0, x.get_grad());
+        assert_close(grads.1, y.get_grad());
+    }
+
+    #[test]
+    fn backward_from_inner_node() {
+        let x = Value::new(0.5);
+        let inner = &x * &x;
+        let outer = inner.sigmoid();
+
+        outer.backward();
+        let upstream = inner.get_grad();
+        let expected = x.get_grad();
+        backward(&[(&inner, upstream)]);
+
+        assert_close(x.get_grad(), expected);
+    }
+
+    #[test]
+    fn forward_after_set_data() {
+        let f = |x: &Value, y: &Value| (x * y + x.exp()).tanh() / y;
+        let x = Value::new(0.5);
+        let y = Value::new(-1.5);
+        let out = f(&x, &y);
+
+        x.set_data(0.2);
+        y.set_data(0.7);
+        out.forward();
+        out.backward();
+
+        let fresh_x = Value::new(0.2);
+        let fresh_y = Value::new(0.7);
+        let fresh = f(&fresh_x, &fresh_y);
+        fresh.backward();
+        assert_close(out.get_data(), fresh.get_data());
+        assert_close(x.get_grad(), fresh_x.get_grad());
+        assert_close(y.get_grad(), fresh_y.get_grad());
+    }
+
+    const DEEP: usize = 200_000;
+
+    #[test]
+    fn deep_chain() {
+        let x = Value::new(0.5);
+        let mut chain = x.clone();
+        for _ in 0..DEEP {
+            chain = &chain * 1.0;
+        }
+        let out = chain.tanh();
+
+        out.backward();
+        assert_close(x.get_grad(), 1.0 - 0.5f32.tanh().powi(2));
+
+        x.set_data(0.0);
+        assert_eq!(out.forward(), 0.0);
+        drop(out);
+        drop(chain);
+    }
+
+    #[test]
+    fn long_sum() {
+        let values: Vec<Value> = (0..DEEP).map(|_| Value::new(1.0)).collect();
+        let sum: Value = values.iter().cloned().sum();
+
+        calculat_grad(&sum);
+
+        assert_eq!(sum.get_data(), DEEP as f32);
+        assert!(values.iter().all(|v| v.get_grad() == 1.0));
+    }
+
+    #[test]
+    fn wide_fan_in() {
+        let x = Value::new(0.5);
+        let sum: Value = (0..DEEP).map(|_| &x * 2.0).sum();
+
+        sum.backward();
+
+        assert_eq!(x.get_grad(), 2.0 * DEEP as f32);
     }
 }
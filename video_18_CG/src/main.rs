@@ -0,0 +1,57 @@
+#![allow(non_snake_case)]
+
+//! Evaluates an expression and prints the gradient of every variable.
+//!
+//! ```text
+//! cargo run -- "tanh(a*b + c) - a" a=0.5 b=-1.5 c=0.2 [--dot graph.dot]
+//! ```
+
+use std::{env, error::Error, fs, process};
+
+use video_18_CG::{DotOptions, Expression, to_dot};
+
+const USAGE: &str = "usage: video_18_CG EXPRESSION [NAME=VALUE]... [--dot FILE]";
+
+fn main() {
+    if let Err(err) = run(env::args().skip(1).collect()) {
+        eprintln!("error: {err}\n{USAGE}");
+        process::exit(2);
+    }
+}
+
+fn run(args: Vec<String>) -> Result<(), Box<dyn Error>> {
+    let mut args = args.into_iter();
+    let source = args.next().ok_or("missing expression")?;
+
+    let mut bindings = Vec::new();
+    let mut dot_path = None;
+    while let Some(arg) = args.next() {
+        if arg == "--dot" {
+            dot_path = Some(args.next().ok_or("--dot needs a file")?);
+            continue;
+        }
+        let (name, value) = arg
+            .split_once('=')
+            .ok_or_else(|| format!("expected NAME=VALUE, found '{arg}'"))?;
+        let value: f32 = value
+            .parse()
+            .map_err(|_| format!("invalid value for {name}: '{value}'"))?;
+        bindings.push((name.to_string(), value));
+    }
+
+    let expression = Expression::parse(&source)?;
+    let bindings: Vec<(&str, f32)> = bindings.iter().map(|(n, v)| (n.as_str(), *v)).collect();
+    let output = expression.evaluate(&bindings)?;
+    expression.output.backward();
+
+    println!("{source} = {output}");
+    for (name, value) in &expression.variables {
+        println!("d/d{name} = {}", value.get_grad());
+    }
+
+    if let Some(path) = dot_path {
+        fs::write(&path, to_dot(&expression.output, &DotOptions::default()))?;
+        println!("graph written to {path}");
+    }
+    Ok(())
+}
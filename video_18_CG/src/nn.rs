@@ -0,0 +1,195 @@
+use rand::Rng;
+
+use crate::Value;
+
+/// Anything with trainable parameters.
+pub trait Module {
+    fn parameters(&self) -> Vec<Value>;
+
+    fn zero_grad(&self) {
+        for p in self.parameters() {
+            p.set_grad(0.0);
+        }
+    }
+}
+
+/// Plain gradient descent: `data -= learning_rate * grad`.
+#[derive(Debug, Clone, Copy)]
+pub struct Sgd {
+    pub learning_rate: f32,
+}
+
+impl Sgd {
+    pub fn new(learning_rate: f32) -> Self {
+        Sgd { learning_rate }
+    }
+
+    pub fn step(&self, module: &impl Module) {
+        for p in module.parameters() {
+            p.set_data(p.get_data() - self.learning_rate * p.get_grad());
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Neuron {
+    weights: Vec<Value>,
+    bias: Value,
+    /// ReLU on the output, linear otherwise.
+    nonlinear: bool,
+}
+
+impl Neuron {
+    pub fn new(inputs: usize, nonlinear: bool, rng: &mut impl Rng) -> Self {
+        Neuron {
+            weights: (0..inputs)
+                .map(|_| Value::new(rng.random_range(-1.0..1.0)))
+                .collect(),
+            bias: Value::new(0.0),
+            nonlinear,
+        }
+    }
+
+    pub fn forward(&self, inputs: &[Value]) -> Value {
+        assert_eq!(inputs.len(), self.weights.len(), "Invalid number of inputs");
+
+        let activation = self
+            .weights
+            .iter()
+            .zip(inputs)
+            .map(|(w, x)| w * x)
+            .fold(self.bias.clone(), |acc, wx| acc + wx);
+
+        if self.nonlinear {
+            activation.relu()
+        } else {
+            activation
+        }
+    }
+}
+
+impl Module for Neuron {
+    fn parameters(&self) -> Vec<Value> {
+        let mut parameters = self.weights.clone();
+        parameters.push(self.bias.clone());
+        parameters
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Layer {
+    neurons: Vec<Neuron>,
+}
+
+impl Layer {
+    pub fn new(inputs: usize, outputs: usize, nonlinear: bool, rng: &mut impl Rng) -> Self {
+        Layer {
+            neurons: (0..outputs)
+                .map(|_| Neuron::new(inputs, nonlinear, rng))
+                .collect(),
+        }
+    }
+
+    pub fn forward(&self, inputs: &[Value]) -> Vec<Value> {
+        self.neurons.iter().map(|n| n.forward(inputs)).collect()
+    }
+}
+
+impl Module for Layer {
+    fn parameters(&self) -> Vec<Value> {
+        self.neurons.iter().flat_map(Neuron::parameters).collect()
+    }
+}
+
+/// Multi-layer perceptron with ReLU hidden layers and a linear output layer.
+#[derive(Debug, Clone)]
+pub struct MLP {
+    layers: Vec<Layer>,
+}
+
+impl MLP {
+    /// `sizes` are the neuron counts of every layer after the `inputs`.
+    pub fn new(inputs: usize, sizes: &[usize], rng: &mut impl Rng) -> Self {
+        let mut layers = vec![];
+        let mut previous = inputs;
+        for (i, &size) in sizes.iter().enumerate() {
+            layers.push(Layer::new(previous, size, i + 1 < sizes.len(), rng));
+            previous = size;
+        }
+        MLP { layers }
+    }
+
+    pub fn forward(&self, inputs: &[Value]) -> Vec<Value> {
+        let mut current = inputs.to_vec();
+        for layer in &self.layers {
+            current = layer.forward(&current);
+        }
+        current
+    }
+}
+
+impl Module for MLP {
+    fn parameters(&self) -> Vec<Value> {
+        self.layers.iter().flat_map(Layer::parameters).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use super::*;
+    use crate::calculat_grad;
+
+    #[test]
+    fn parameter_count() {
+        let mlp = MLP::new(3, &[4, 4, 1], &mut StdRng::seed_from_u64(0));
+
+        assert_eq!(mlp.parameters().len(), 4 * 4 + 4 * 5 + 5);
+    }
+
+    #[test]
+    fn training_reduces_loss() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let mlp = MLP::new(2, &[8, 1], &mut rng);
+        let sgd = Sgd::new(0.05);
+        let samples = [
+            ([0.0, 1.0], 1.0),
+            ([1.0, 0.0], 1.0),
+            ([0.0, 0.0], -1.0),
+            ([1.0, 1.0], -1.0),
+        ];
+
+        let loss = || -> Value {
+            samples
+                .iter()
+                .map(|(x, y)| {
+                    let inputs: Vec<Value> = x.iter().map(|&v| Value::new(v)).collect();
+                    (&mlp.forward(&inputs)[0] - *y).pow(2.0)
+                })
+                .sum()
+        };
+
+        let first = loss().get_data();
+        for _ in 0..200 {
+            let loss = loss();
+            mlp.zero_grad();
+            calculat_grad(&loss);
+            sgd.step(&mlp);
+        }
+        let last = loss().get_data();
+
+        assert!(last < first * 0.5, "loss went from {first} to {last}");
+    }
+
+    #[test]
+    fn zero_grad_resets_parameters() {
+        let neuron = Neuron::new(2, true, &mut StdRng::seed_from_u64(2));
+        let out = neuron.forward(&[Value::new(1.0), Value::new(2.0)]) * 3.0;
+        calculat_grad(&out);
+
+        neuron.zero_grad();
+
+        assert!(neuron.parameters().iter().all(|p| p.get_grad() == 0.0));
+    }
+}
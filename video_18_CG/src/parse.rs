@@ -0,0 +1,473 @@
+use std::fmt;
+
+use crate::Value;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExprError {
+    /// `position` is a 0-based character offset into the source.
+    UnexpectedChar {
+        position: usize,
+        found: char,
+    },
+    UnexpectedToken {
+        position: usize,
+        found: String,
+        expected: &'static str,
+    },
+    UnexpectedEnd {
+        expected: &'static str,
+    },
+    UnknownFunction {
+        position: usize,
+        name: String,
+    },
+    /// Parentheses, function calls and unary minuses nested more than `limit` deep.
+    TooDeep {
+        position: usize,
+        limit: usize,
+    },
+    /// A binding names a variable the expression doesn't use.
+    UnknownVariable(String),
+    UnboundVariable(String),
+}
+
+impl fmt::Display for ExprError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExprError::UnexpectedChar { position, found } => {
+                write!(f, "unexpected character '{found}' at {position}")
+            }
+            ExprError::UnexpectedToken {
+                position,
+                found,
+                expected,
+            } => write!(f, "expected {expected} at {position}, found '{found}'"),
+            ExprError::UnexpectedEnd { expected } => {
+                write!(f, "expected {expected}, found end of input")
+            }
+            ExprError::UnknownFunction { position, name } => {
+                write!(f, "unknown function '{name}' at {position}")
+            }
+            ExprError::TooDeep { position, limit } => {
+                write!(f, "nesting deeper than {limit} levels at {position}")
+            }
+            ExprError::UnknownVariable(name) => write!(f, "unknown variable '{name}'"),
+            ExprError::UnboundVariable(name) => write!(f, "no value for variable '{name}'"),
+        }
+    }
+}
+
+impl std::error::Error for ExprError {}
+
+/// An arithmetic expression compiled to a [`Value`] graph.
+///
+/// Supports numbers, variables, `+ - * /`, unary minus, `^` with a constant
+/// exponent and the functions `exp`, `log`, `relu`, `sigmoid`, `sqrt` and `tanh`.
+/// Every distinct variable is one named leaf, so the graph is built once and
+/// re-evaluated through [`Expression::evaluate`].
+#[derive(Debug, Clone)]
+pub struct Expression {
+    pub output: Value,
+    /// In order of first appearance.
+    pub variables: Vec<(String, Value)>,
+}
+
+impl Expression {
+    pub fn parse(source: &str) -> Result<Expression, ExprError> {
+        let mut parser = Parser {
+            tokens: tokenize(source)?,
+            next: 0,
+            depth: 0,
+            variables: Vec::new(),
+        };
+        let output = parser.expression()?;
+        if let Some((position, token)) = parser.tokens.get(parser.next) {
+            return Err(ExprError::UnexpectedToken {
+                position: *position,
+                found: token.to_string(),
+                expected: "an operator",
+            });
+        }
+        Ok(Expression {
+            output,
+            variables: parser.variables,
+        })
+    }
+
+    pub fn variable(&self, name: &str) -> Option<&Value> {
+        self.variables
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value)
+    }
+
+    /// Binds every variable and recomputes the output, leaving grads untouched.
+    pub fn evaluate(&self, bindings: &[(&str, f32)]) -> Result<f32, ExprError> {
+        for (name, _) in bindings {
+            if self.variable(name).is_none() {
+                return Err(ExprError::UnknownVariable(name.to_string()));
+            }
+        }
+        for (name, value) in &self.variables {
+            let (_, data) = bindings
+                .iter()
+                .find(|(n, _)| n == name)
+                .ok_or_else(|| ExprError::UnboundVariable(name.clone()))?;
+            value.set_data(*data);
+        }
+        Ok(self.output.forward())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f32),
+    Ident(String),
+    Symbol(char),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Number(n) => write!(f, "{n}"),
+            Token::Ident(name) => write!(f, "{name}"),
+            Token::Symbol(c) => write!(f, "{c}"),
+        }
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<(usize, Token)>, ExprError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let start = i;
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        let token = if c.is_ascii_digit() || c == '.' {
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().collect();
+            let number = text.parse().map_err(|_| ExprError::UnexpectedToken {
+                position: start,
+                found: text,
+                expected: "a number",
+            })?;
+            Token::Number(number)
+        } else if c.is_alphabetic() || c == '_' {
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            Token::Ident(chars[start..i].iter().collect())
+        } else if "+-*/^()".contains(c) {
+            i += 1;
+            Token::Symbol(c)
+        } else {
+            return Err(ExprError::UnexpectedChar {
+                position: start,
+                found: c,
+            });
+        };
+        tokens.push((start, token));
+    }
+    Ok(tokens)
+}
+
+/// Recursive descent, loosest binding first:
+///
+/// ```text
+/// expression = term (("+" | "-") term)*
+/// term       = unary (("*" | "/") unary)*
+/// unary      = "-" unary | power
+/// power      = atom ("^" "-"? number)?
+/// atom       = number | name | name "(" expression ")" | "(" expression ")"
+/// ```
+///
+/// Nesting is capped at [`MAX_DEPTH`] so a deep input fails with
+/// [`ExprError::TooDeep`] instead of overflowing the stack.
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    next: usize,
+    depth: usize,
+    variables: Vec<(String, Value)>,
+}
+
+const MAX_DEPTH: usize = 256;
+
+impl Parser {
+    fn peek_symbol(&self) -> Option<char> {
+        match self.tokens.get(self.next) {
+            Some((_, Token::Symbol(c))) => Some(*c),
+            _ => None,
+        }
+    }
+
+    fn advance(&mut self, expected: &'static str) -> Result<(usize, Token), ExprError> {
+        let token = self
+            .tokens
+            .get(self.next)
+            .cloned()
+            .ok_or(ExprError::UnexpectedEnd { expected })?;
+        self.next += 1;
+        Ok(token)
+    }
+
+    fn expect_symbol(&mut self, symbol: char, expected: &'static str) -> Result<(), ExprError> {
+        match self.advance(expected)? {
+            (_, Token::Symbol(c)) if c == symbol => Ok(()),
+            (position, token) => Err(ExprError::UnexpectedToken {
+                position,
+                found: token.to_string(),
+                expected,
+            }),
+        }
+    }
+
+    /// Runs `parse` one nesting level deeper, `position` being where that level opens.
+    fn nested(
+        &mut self,
+        position: usize,
+        parse: impl FnOnce(&mut Self) -> Result<Value, ExprError>,
+    ) -> Result<Value, ExprError> {
+        if self.depth == MAX_DEPTH {
+            return Err(ExprError::TooDeep {
+                position,
+                limit: MAX_DEPTH,
+            });
+        }
+        self.depth += 1;
+        let value = parse(self);
+        self.depth -= 1;
+        value
+    }
+
+    fn expression(&mut self) -> Result<Value, ExprError> {
+        let mut value = self.term()?;
+        while let Some(op @ ('+' | '-')) = self.peek_symbol() {
+            self.next += 1;
+            let rhs = self.term()?;
+            value = if op == '+' { value + rhs } else { value - rhs };
+        }
+        Ok(value)
+    }
+
+    fn term(&mut self) -> Result<Value, ExprError> {
+        let mut value = self.unary()?;
+        while let Some(op @ ('*' | '/')) = self.peek_symbol() {
+            self.next += 1;
+            let rhs = self.unary()?;
+            value = if op == '*' { value * rhs } else { value / rhs };
+        }
+        Ok(value)
+    }
+
+    fn unary(&mut self) -> Result<Value, ExprError> {
+        if self.peek_symbol() == Some('-') {
+            let (position, _) = self.tokens[self.next];
+            self.next += 1;
+            return Ok(-self.nested(position, Self::unary)?);
+        }
+        self.power()
+    }
+
+    fn power(&mut self) -> Result<Value, ExprError> {
+        let base = self.atom()?;
+        if self.peek_symbol() != Some('^') {
+            return Ok(base);
+        }
+        self.next += 1;
+        let negative = self.peek_symbol() == Some('-');
+        if negative {
+            self.next += 1;
+        }
+        match self.advance("a constant exponent")? {
+            (_, Token::Number(n)) => Ok(base.pow(if negative { -n } else { n })),
+            (position, token) => Err(ExprError::UnexpectedToken {
+                position,
+                found: token.to_string(),
+                expected: "a constant exponent",
+            }),
+        }
+    }
+
+    fn atom(&mut self) -> Result<Value, ExprError> {
+        match self.advance("a number, variable or '('")? {
+            (_, Token::Number(n)) => Ok(Value::new(n)),
+            (position, Token::Symbol('(')) => {
+                let value = self.nested(position, Self::expression)?;
+                self.expect_symbol(')', "')'")?;
+                Ok(value)
+            }
+            (position, Token::Ident(name)) if self.peek_symbol() == Some('(') => {
+                self.next += 1;
+                let argument = self.nested(position, Self::expression)?;
+                self.expect_symbol(')', "')'")?;
+                match name.as_str() {
+                    "exp" => Ok(argument.exp()),
+                    "log" => Ok(argument.log()),
+                    "relu" => Ok(argument.relu()),
+                    "sigmoid" => Ok(argument.sigmoid()),
+                    "sqrt" => Ok(argument.pow(0.5)),
+                    "tanh" => Ok(argument.tanh()),
+                    _ => Err(ExprError::UnknownFunction { position, name }),
+                }
+            }
+            (_, Token::Ident(name)) => Ok(self.variable(name)),
+            (position, token) => Err(ExprError::UnexpectedToken {
+                position,
+                found: token.to_string(),
+                expected: "a number, variable or '('",
+            }),
+        }
+    }
+
+    fn variable(&mut self, name: String) -> Value {
+        if let Some((_, value)) = self.variables.iter().find(|(n, _)| *n == name) {
+            return value.clone();
+        }
+        let value = Value::named(0.0, name.clone());
+        self.variables.push((name, value.clone()));
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(source: &str, bindings: &[(&str, f32)]) -> f32 {
+        Expression::parse(source)
+            .unwrap()
+            .evaluate(bindings)
+            .unwrap()
+    }
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!((actual - expected).abs() < 1e-5, "{actual} != {expected}");
+    }
+
+    #[test]
+    fn precedence_and_associativity() {
+        assert_close(eval("1 + 2 * 3", &[]), 7.0);
+        assert_close(eval("(1 + 2) * 3", &[]), 9.0);
+        assert_close(eval("8 - 3 - 2", &[]), 3.0);
+        assert_close(eval("8 / 4 / 2", &[]), 1.0);
+        assert_close(eval("-2^2", &[]), -4.0);
+        assert_close(eval("2^-1 + -(-3)", &[]), 3.5);
+        assert_close(eval("x * -y", &[("x", 2.0), ("y", 3.0)]), -6.0);
+    }
+
+    #[test]
+    fn functions_and_gradients() {
+        let expression = Expression::parse("tanh(a*b + c) - a").unwrap();
+        let (a, b, c) = (0.5, -1.5, 0.2);
+        let output = expression
+            .evaluate(&[("c", c), ("b", b), ("a", a)])
+            .unwrap();
+        expression.output.backward();
+
+        let t = (a * b + c).tanh();
+        assert_close(output, t - a);
+        let names: Vec<&str> = expression
+            .variables
+            .iter()
+            .map(|(n, _)| n.as_str())
+            .collect();
+        assert_eq!(names, ["a", "b", "c"]);
+        let grad = |name| expression.variable(name).unwrap().get_grad();
+        assert_close(grad("a"), (1.0 - t * t) * b - 1.0);
+        assert_close(grad("b"), (1.0 - t * t) * a);
+        assert_close(grad("c"), 1.0 - t * t);
+
+        assert_close(
+            eval(
+                "sqrt(x) + exp(log(x)) + relu(-x) + sigmoid(0)",
+                &[("x", 4.0)],
+            ),
+            6.5,
+        );
+    }
+
+    #[test]
+    fn reevaluates_without_reparsing() {
+        let expression = Expression::parse("x * x + y").unwrap();
+
+        assert_close(expression.evaluate(&[("x", 2.0), ("y", 1.0)]).unwrap(), 5.0);
+        assert_close(expression.evaluate(&[("x", 3.0), ("y", 0.0)]).unwrap(), 9.0);
+        assert_eq!(
+            expression.variable("x").unwrap().name().as_deref(),
+            Some("x")
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let err = |source| Expression::parse(source).unwrap_err();
+
+        assert_eq!(
+            err("1 + $"),
+            ExprError::UnexpectedChar {
+                position: 4,
+                found: '$'
+            }
+        );
+        assert_eq!(err("(1 + 2"), ExprError::UnexpectedEnd { expected: "')'" });
+        assert_eq!(
+            err("1 2"),
+            ExprError::UnexpectedToken {
+                position: 2,
+                found: "2".to_string(),
+                expected: "an operator"
+            }
+        );
+        assert_eq!(
+            err("cos(x)"),
+            ExprError::UnknownFunction {
+                position: 0,
+                name: "cos".to_string()
+            }
+        );
+        assert!(matches!(
+            err("x ^ y"),
+            ExprError::UnexpectedToken { position: 4, .. }
+        ));
+        assert!(matches!(err("1.2.3"), ExprError::UnexpectedToken { .. }));
+
+        let expression = Expression::parse("x + y").unwrap();
+        assert_eq!(
+            expression.evaluate(&[("x", 1.0)]),
+            Err(ExprError::UnboundVariable("y".to_string()))
+        );
+        assert_eq!(
+            expression.evaluate(&[("x", 1.0), ("y", 1.0), ("z", 1.0)]),
+            Err(ExprError::UnknownVariable("z".to_string()))
+        );
+    }
+
+    #[test]
+    fn nesting_depth_is_limited() {
+        let nested = |depth| format!("{}x{}", "(".repeat(depth), ")".repeat(depth));
+
+        assert_close(eval(&nested(MAX_DEPTH), &[("x", 2.0)]), 2.0);
+        assert_close(eval(&format!("{}1", "-".repeat(MAX_DEPTH)), &[]), 1.0);
+        assert_eq!(
+            Expression::parse(&nested(50_000)).unwrap_err(),
+            ExprError::TooDeep {
+                position: MAX_DEPTH,
+                limit: MAX_DEPTH
+            }
+        );
+        assert!(matches!(
+            Expression::parse(&format!("{}1", "-".repeat(50_000))).unwrap_err(),
+            ExprError::TooDeep { .. }
+        ));
+        assert!(matches!(
+            Expression::parse(&format!("{}1{}", "exp(".repeat(50_000), ")".repeat(50_000))),
+            Err(ExprError::TooDeep { .. })
+        ));
+    }
+}
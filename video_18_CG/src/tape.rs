@@ -0,0 +1,383 @@
+use std::{cell::RefCell, fmt};
+
+/// A node on a [`Tape`], borrowing the tape it was recorded on.
+///
+/// Ops build new nodes on that tape, like [`Value`](crate::Value) ops build new
+/// values. Combining vars of different tapes panics.
+#[derive(Clone, Copy)]
+pub struct Var<'t> {
+    tape: &'t Tape,
+    index: usize,
+}
+
+/// Arena-backed alternative to [`Value`](crate::Value): nodes live in one `Vec` and
+/// refer to their operands by index.
+///
+/// A node is always pushed after its operands, so the tape itself is a topological
+/// order and neither pass needs a graph traversal. Tapes hold no shared state, so
+/// every thread can build and differentiate its own.
+#[derive(Debug, Clone, Default)]
+pub struct Tape {
+    nodes: RefCell<Vec<Node>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Node {
+    data: f32,
+    grad: f32,
+    op: TapeOp,
+}
+
+/// Operands are indices on the same tape.
+#[derive(Debug, Clone, Copy)]
+enum TapeOp {
+    Leaf,
+    Add(usize, usize),
+    Sub(usize, usize),
+    Mul(usize, usize),
+    Div(usize, usize),
+    Neg(usize),
+    Pow(usize, f32),
+    Exp(usize),
+    Log(usize),
+    Relu(usize),
+    Sigmoid(usize),
+    Tanh(usize),
+}
+
+impl Tape {
+    pub fn new() -> Self {
+        Tape::default()
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Tape {
+            nodes: RefCell::new(Vec::with_capacity(capacity)),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.borrow().is_empty()
+    }
+
+    pub fn leaf(&self, data: f32) -> Var<'_> {
+        self.push(data, TapeOp::Leaf)
+    }
+
+    pub fn zero_grad(&self) {
+        for node in self.nodes.borrow_mut().iter_mut() {
+            node.grad = 0.0;
+        }
+    }
+
+    /// Recomputes every node from the current leaf data.
+    pub fn forward(&self) {
+        let mut nodes = self.nodes.borrow_mut();
+        for i in 0..nodes.len() {
+            if let Some(data) = compute(&nodes, nodes[i].op) {
+                nodes[i].data = data;
+            }
+        }
+    }
+
+    /// Replaces all grads with `d root / d node`.
+    pub fn backward(&self, root: Var) {
+        self.check(root);
+        self.zero_grad();
+
+        let mut nodes = self.nodes.borrow_mut();
+        nodes[root.index].grad = 1.0;
+        // Nodes after `root` can't contribute to it.
+        for i in (0..=root.index).rev() {
+            let Node { data, grad, op } = nodes[i];
+            match op {
+                TapeOp::Leaf => {}
+                TapeOp::Add(lhs, rhs) => {
+                    nodes[lhs].grad += grad;
+                    nodes[rhs].grad += grad;
+                }
+                TapeOp::Sub(lhs, rhs) => {
+                    nodes[lhs].grad += grad;
+                    nodes[rhs].grad -= grad;
+                }
+                TapeOp::Mul(lhs, rhs) => {
+                    let (l, r) = (nodes[lhs].data, nodes[rhs].data);
+                    nodes[lhs].grad += grad * r;
+                    nodes[rhs].grad += grad * l;
+                }
+                TapeOp::Div(lhs, rhs) => {
+                    let (l, r) = (nodes[lhs].data, nodes[rhs].data);
+                    nodes[lhs].grad += grad / r;
+                    nodes[rhs].grad -= grad * l / (r * r);
+                }
+                TapeOp::Neg(v) => nodes[v].grad -= grad,
+                TapeOp::Pow(v, exponent) => {
+                    let local_grad = exponent * nodes[v].data.powf(exponent - 1.0);
+                    nodes[v].grad += grad * local_grad;
+                }
+                TapeOp::Exp(v) => nodes[v].grad += grad * data,
+                TapeOp::Log(v) => nodes[v].grad += grad / nodes[v].data,
+                TapeOp::Relu(v) => {
+                    if data > 0.0 {
+                        nodes[v].grad += grad;
+                    }
+                }
+                TapeOp::Sigmoid(v) => nodes[v].grad += grad * data * (1.0 - data),
+                TapeOp::Tanh(v) => nodes[v].grad += grad * (1.0 - data * data),
+            }
+        }
+    }
+
+    fn check(&self, v: Var) {
+        assert!(
+            std::ptr::eq(self, v.tape),
+            "Var {} belongs to another tape",
+            v.index
+        );
+    }
+
+    fn apply(&self, op: TapeOp) -> Var<'_> {
+        let data = compute(&self.nodes.borrow(), op).expect("ops have operands");
+        self.push(data, op)
+    }
+
+    fn push(&self, data: f32, op: TapeOp) -> Var<'_> {
+        let mut nodes = self.nodes.borrow_mut();
+        nodes.push(Node {
+            data,
+            grad: 0.0,
+            op,
+        });
+        Var {
+            tape: self,
+            index: nodes.len() - 1,
+        }
+    }
+}
+
+fn compute(nodes: &[Node], op: TapeOp) -> Option<f32> {
+    let d = |v: usize| nodes[v].data;
+    let data = match op {
+        TapeOp::Leaf => return None,
+        TapeOp::Add(lhs, rhs) => d(lhs) + d(rhs),
+        TapeOp::Sub(lhs, rhs) => d(lhs) - d(rhs),
+        TapeOp::Mul(lhs, rhs) => d(lhs) * d(rhs),
+        TapeOp::Div(lhs, rhs) => d(lhs) / d(rhs),
+        TapeOp::Neg(v) => -d(v),
+        TapeOp::Pow(v, exponent) => d(v).powf(exponent),
+        TapeOp::Exp(v) => d(v).exp(),
+        TapeOp::Log(v) => d(v).ln(),
+        TapeOp::Relu(v) => d(v).max(0.0),
+        TapeOp::Sigmoid(v) => 1.0 / (1.0 + (-d(v)).exp()),
+        TapeOp::Tanh(v) => d(v).tanh(),
+    };
+    Some(data)
+}
+
+impl<'t> Var<'t> {
+    pub fn data(&self) -> f32 {
+        self.tape.nodes.borrow()[self.index].data
+    }
+
+    pub fn grad(&self) -> f32 {
+        self.tape.nodes.borrow()[self.index].grad
+    }
+
+    /// Changes a leaf; call [`Tape::forward`] to update the nodes depending on it.
+    pub fn set_data(&self, data: f32) {
+        self.tape.nodes.borrow_mut()[self.index].data = data;
+    }
+
+    /// Raised to a constant exponent.
+    pub fn pow(self, exponent: f32) -> Var<'t> {
+        self.tape.apply(TapeOp::Pow(self.index, exponent))
+    }
+
+    pub fn exp(self) -> Var<'t> {
+        self.tape.apply(TapeOp::Exp(self.index))
+    }
+
+    /// Natural logarithm.
+    pub fn log(self) -> Var<'t> {
+        self.tape.apply(TapeOp::Log(self.index))
+    }
+
+    pub fn relu(self) -> Var<'t> {
+        self.tape.apply(TapeOp::Relu(self.index))
+    }
+
+    pub fn sigmoid(self) -> Var<'t> {
+        self.tape.apply(TapeOp::Sigmoid(self.index))
+    }
+
+    pub fn tanh(self) -> Var<'t> {
+        self.tape.apply(TapeOp::Tanh(self.index))
+    }
+
+    fn binary(self, rhs: Var<'t>, op: fn(usize, usize) -> TapeOp) -> Var<'t> {
+        self.tape.check(rhs);
+        self.tape.apply(op(self.index, rhs.index))
+    }
+}
+
+impl fmt::Debug for Var<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Var({}, data={})", self.index, self.data())
+    }
+}
+
+impl PartialEq for Var<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self.tape, other.tape) && self.index == other.index
+    }
+}
+
+impl Eq for Var<'_> {}
+
+impl<'t> std::ops::Neg for Var<'t> {
+    type Output = Var<'t>;
+    fn neg(self) -> Var<'t> {
+        self.tape.apply(TapeOp::Neg(self.index))
+    }
+}
+
+/// `Var op Var`, plus `f32` on either side as a new leaf on the var's tape.
+macro_rules! var_binary_op {
+    ($trait:ident, $method:ident, $op:ident) => {
+        impl<'t> std::ops::$trait for Var<'t> {
+            type Output = Var<'t>;
+            fn $method(self, rhs: Var<'t>) -> Var<'t> {
+                self.binary(rhs, TapeOp::$op)
+            }
+        }
+
+        impl<'t> std::ops::$trait<f32> for Var<'t> {
+            type Output = Var<'t>;
+            fn $method(self, rhs: f32) -> Var<'t> {
+                self.binary(self.tape.leaf(rhs), TapeOp::$op)
+            }
+        }
+
+        impl<'t> std::ops::$trait<Var<'t>> for f32 {
+            type Output = Var<'t>;
+            fn $method(self, rhs: Var<'t>) -> Var<'t> {
+                rhs.tape.leaf(self).binary(rhs, TapeOp::$op)
+            }
+        }
+    };
+}
+
+var_binary_op!(Add, add, Add);
+var_binary_op!(Sub, sub, Sub);
+var_binary_op!(Mul, mul, Mul);
+var_binary_op!(Div, div, Div);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Value;
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!((actual - expected).abs() < 1e-5, "{actual} != {expected}");
+    }
+
+    /// The composite expression of the `Value` gradient tests on a tape.
+    fn composite<'t>(x: Var<'t>, y: Var<'t>, z: Var<'t>) -> Var<'t> {
+        let a = (x * y + z.exp()).tanh();
+        let b = (y / 2.0 - z).sigmoid().log();
+        -(a * b - x.pow(2.0).relu())
+    }
+
+    #[test]
+    fn matches_rc_graph() {
+        let inputs = [0.4, -0.7, 0.2];
+        let tape = Tape::new();
+        let [x, y, z] = inputs.map(|d| tape.leaf(d));
+        let out = composite(x, y, z);
+        tape.backward(out);
+
+        let [vx, vy, vz] = inputs.map(Value::new);
+        let a = (&vx * &vy + vz.exp()).tanh();
+        let b = (&vy / 2.0 - &vz).sigmoid().log();
+        let value = -(a * b - vx.pow(2.0).relu());
+        value.backward();
+
+        assert_close(out.data(), value.get_data());
+        for (var, value) in [(x, vx), (y, vy), (z, vz)] {
+            assert_close(var.grad(), value.get_grad());
+        }
+    }
+
+    #[test]
+    fn forward_after_set_data() {
+        let tape = Tape::new();
+        let [x, y, z] = [0.4, -0.7, 0.2].map(|d| tape.leaf(d));
+        let out = composite(x, y, z);
+
+        x.set_data(-0.3);
+        tape.forward();
+        tape.backward(out);
+
+        let fresh = Tape::new();
+        let [fx, fy, fz] = [-0.3, -0.7, 0.2].map(|d| fresh.leaf(d));
+        let fresh_out = composite(fx, fy, fz);
+        fresh.backward(fresh_out);
+        assert_close(out.data(), fresh_out.data());
+        assert_close(x.grad(), fx.grad());
+    }
+
+    #[test]
+    fn independent_tapes_per_thread() {
+        let results: Vec<(f32, f32)> = std::thread::scope(|s| {
+            let handles: Vec<_> = (0..4)
+                .map(|i| {
+                    s.spawn(move || {
+                        let tape = Tape::new();
+                        let x = tape.leaf(i as f32);
+                        let mut sum = tape.leaf(0.0);
+                        for _ in 0..100_000 {
+                            sum = sum + x * x;
+                        }
+                        tape.backward(sum);
+                        (sum.data(), x.grad())
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+
+        for (i, (data, grad)) in results.into_iter().enumerate() {
+            let i = i as f32;
+            assert_eq!(data, 100_000.0 * i * i);
+            assert_eq!(grad, 200_000.0 * i);
+        }
+    }
+
+    #[test]
+    fn backward_ignores_later_nodes() {
+        let tape = Tape::new();
+        let x = tape.leaf(3.0);
+        let y = x * x;
+        let later = y.exp();
+
+        tape.backward(y);
+        tape.backward(y);
+
+        assert_eq!(x.grad(), 6.0);
+        assert_eq!(later.grad(), 0.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Var 0 belongs to another tape")]
+    fn vars_of_different_tapes_do_not_mix() {
+        let (first, second) = (Tape::new(), Tape::new());
+        let x = first.leaf(1.0);
+        let y = second.leaf(2.0);
+
+        let _ = y * x;
+    }
+}
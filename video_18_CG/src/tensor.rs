@@ -0,0 +1,732 @@
+use std::{cell::RefCell, collections::HashSet, ops::Deref, rc::Rc};
+
+use crate::{forward_binary_op, get_id};
+
+/// A node holding a row-major array of `f32`, the tensor counterpart of [`Value`](crate::Value).
+///
+/// Elementwise ops broadcast like NumPy: shapes are aligned from the last axis and
+/// axes of size 1 are repeated. A shape of `[]` is a scalar.
+#[derive(Debug, Clone)]
+pub struct Tensor(Rc<Tensor_>);
+
+#[derive(Debug)]
+pub struct Tensor_ {
+    id: usize,
+    shape: Vec<usize>,
+    data: RefCell<Vec<f32>>,
+    grad: RefCell<Vec<f32>>,
+    op: TensorOp,
+}
+
+#[derive(Debug)]
+enum TensorOp {
+    None,
+    Add(Tensor, Tensor),
+    Sub(Tensor, Tensor),
+    Mul(Tensor, Tensor),
+    Div(Tensor, Tensor),
+    Neg(Tensor),
+    Pow(Tensor, f32),
+    Exp(Tensor),
+    Log(Tensor),
+    Relu(Tensor),
+    Sigmoid(Tensor),
+    Tanh(Tensor),
+    MatMul(Tensor, Tensor),
+    Sum(Tensor),
+    SumAxis(Tensor, usize),
+    Reshape(Tensor),
+}
+
+impl TensorOp {
+    fn into_operands(self) -> Vec<Tensor> {
+        match self {
+            TensorOp::None => vec![],
+            TensorOp::Add(lhs, rhs)
+            | TensorOp::Sub(lhs, rhs)
+            | TensorOp::Mul(lhs, rhs)
+            | TensorOp::Div(lhs, rhs)
+            | TensorOp::MatMul(lhs, rhs) => vec![lhs, rhs],
+            TensorOp::Neg(inner)
+            | TensorOp::Pow(inner, _)
+            | TensorOp::Exp(inner)
+            | TensorOp::Log(inner)
+            | TensorOp::Relu(inner)
+            | TensorOp::Sigmoid(inner)
+            | TensorOp::Tanh(inner)
+            | TensorOp::Sum(inner)
+            | TensorOp::SumAxis(inner, _)
+            | TensorOp::Reshape(inner) => vec![inner],
+        }
+    }
+
+    fn operands(&self) -> Vec<&Tensor> {
+        match self {
+            TensorOp::None => vec![],
+            TensorOp::Add(lhs, rhs)
+            | TensorOp::Sub(lhs, rhs)
+            | TensorOp::Mul(lhs, rhs)
+            | TensorOp::Div(lhs, rhs)
+            | TensorOp::MatMul(lhs, rhs) => vec![lhs, rhs],
+            TensorOp::Neg(inner)
+            | TensorOp::Pow(inner, _)
+            | TensorOp::Exp(inner)
+            | TensorOp::Log(inner)
+            | TensorOp::Relu(inner)
+            | TensorOp::Sigmoid(inner)
+            | TensorOp::Tanh(inner)
+            | TensorOp::Sum(inner)
+            | TensorOp::SumAxis(inner, _)
+            | TensorOp::Reshape(inner) => vec![inner],
+        }
+    }
+}
+
+impl Tensor {
+    pub fn new(data: Vec<f32>, shape: &[usize]) -> Self {
+        assert_eq!(
+            data.len(),
+            numel(shape),
+            "{} values don't fit shape {shape:?}",
+            data.len()
+        );
+        Tensor::with_op(data, shape.to_vec(), TensorOp::None)
+    }
+
+    pub fn scalar(data: f32) -> Self {
+        Tensor::new(vec![data], &[])
+    }
+
+    pub fn zeros(shape: &[usize]) -> Self {
+        Tensor::new(vec![0.0; numel(shape)], shape)
+    }
+
+    fn with_op(data: Vec<f32>, shape: Vec<usize>, op: TensorOp) -> Self {
+        let grad = vec![0.0; data.len()];
+        Tensor(Rc::new(Tensor_ {
+            id: get_id(),
+            shape,
+            data: RefCell::new(data),
+            grad: RefCell::new(grad),
+            op,
+        }))
+    }
+
+    fn map(&self, f: impl Fn(f32) -> f32, op: TensorOp) -> Tensor {
+        let data = self.data.borrow().iter().map(|&x| f(x)).collect();
+        Tensor::with_op(data, self.shape.clone(), op)
+    }
+
+    pub fn pow(&self, exponent: f32) -> Tensor {
+        self.map(|x| x.powf(exponent), TensorOp::Pow(self.clone(), exponent))
+    }
+
+    pub fn exp(&self) -> Tensor {
+        self.map(f32::exp, TensorOp::Exp(self.clone()))
+    }
+
+    /// Natural logarithm.
+    pub fn log(&self) -> Tensor {
+        self.map(f32::ln, TensorOp::Log(self.clone()))
+    }
+
+    pub fn relu(&self) -> Tensor {
+        self.map(|x| x.max(0.0), TensorOp::Relu(self.clone()))
+    }
+
+    pub fn sigmoid(&self) -> Tensor {
+        self.map(
+            |x| 1.0 / (1.0 + (-x).exp()),
+            TensorOp::Sigmoid(self.clone()),
+        )
+    }
+
+    pub fn tanh(&self) -> Tensor {
+        self.map(f32::tanh, TensorOp::Tanh(self.clone()))
+    }
+
+    /// Matrix product of an `m x k` and a `k x n` tensor.
+    pub fn matmul(&self, rhs: &Tensor) -> Tensor {
+        let (m, k, n) = match (self.shape(), rhs.shape()) {
+            (&[m, k], &[k2, n]) if k == k2 => (m, k, n),
+            (lhs, rhs) => panic!("matmul: incompatible shapes {lhs:?} and {rhs:?}"),
+        };
+        let data = matmul(&self.data.borrow(), &rhs.data.borrow(), m, k, n);
+        Tensor::with_op(
+            data,
+            vec![m, n],
+            TensorOp::MatMul(self.clone(), rhs.clone()),
+        )
+    }
+
+    /// Sum of all elements, as a scalar.
+    pub fn sum(&self) -> Tensor {
+        let total = self.data.borrow().iter().sum();
+        Tensor::with_op(vec![total], vec![], TensorOp::Sum(self.clone()))
+    }
+
+    pub fn mean(&self) -> Tensor {
+        self.sum() * (1.0 / self.len() as f32)
+    }
+
+    /// Sums over `axis`, removing it from the shape.
+    pub fn sum_axis(&self, axis: usize) -> Tensor {
+        assert!(
+            axis < self.shape.len(),
+            "axis {axis} out of range for shape {:?}",
+            self.shape
+        );
+        let (outer, len, inner) = split_at_axis(&self.shape, axis);
+        let input = self.data.borrow();
+        let mut data = vec![0.0; outer * inner];
+        for o in 0..outer {
+            for j in 0..len {
+                let row = &input[(o * len + j) * inner..][..inner];
+                for (sum, x) in data[o * inner..][..inner].iter_mut().zip(row) {
+                    *sum += x;
+                }
+            }
+        }
+        let mut shape = self.shape.clone();
+        shape.remove(axis);
+        Tensor::with_op(data, shape, TensorOp::SumAxis(self.clone(), axis))
+    }
+
+    pub fn mean_axis(&self, axis: usize) -> Tensor {
+        self.sum_axis(axis) * (1.0 / self.shape[axis] as f32)
+    }
+
+    /// Same data in a new shape with as many elements.
+    pub fn reshape(&self, shape: &[usize]) -> Tensor {
+        assert_eq!(
+            numel(shape),
+            self.len(),
+            "cannot reshape {:?} into {shape:?}",
+            self.shape
+        );
+        let data = self.data.borrow().clone();
+        Tensor::with_op(data, shape.to_vec(), TensorOp::Reshape(self.clone()))
+    }
+
+    pub fn zero_grad(&self) {
+        for t in topological_order(self) {
+            t.grad.borrow_mut().fill(0.0);
+        }
+    }
+
+    /// Replaces the grads in the graph of a single-element tensor with
+    /// `d self / d node`.
+    pub fn backward(&self) {
+        assert_eq!(self.len(), 1, "backward needs a single-element tensor");
+        let order = topological_order(self);
+        for t in &order {
+            t.grad.borrow_mut().fill(0.0);
+        }
+        self.grad.borrow_mut()[0] = 1.0;
+        for t in order.iter().rev() {
+            t.propagate();
+        }
+    }
+
+    fn propagate(&self) {
+        let grad = self.grad.borrow();
+        let data = self.data.borrow();
+        match &self.op {
+            TensorOp::None => {}
+            TensorOp::Add(lhs, rhs) => self.propagate_binary(lhs, rhs, |_, _| (1.0, 1.0)),
+            TensorOp::Sub(lhs, rhs) => self.propagate_binary(lhs, rhs, |_, _| (1.0, -1.0)),
+            TensorOp::Mul(lhs, rhs) => self.propagate_binary(lhs, rhs, |a, b| (b, a)),
+            TensorOp::Div(lhs, rhs) => {
+                self.propagate_binary(lhs, rhs, |a, b| (1.0 / b, -a / (b * b)))
+            }
+            TensorOp::Neg(inner) => inner.add_grad(grad.iter().map(|g| -g)),
+            TensorOp::Pow(inner, exponent) => {
+                let input = inner.data.borrow();
+                inner.add_grad(
+                    grad.iter()
+                        .zip(input.iter())
+                        .map(|(g, x)| g * exponent * x.powf(exponent - 1.0)),
+                );
+            }
+            TensorOp::Exp(inner) => {
+                inner.add_grad(grad.iter().zip(data.iter()).map(|(g, y)| g * y))
+            }
+            TensorOp::Log(inner) => {
+                let input = inner.data.borrow();
+                inner.add_grad(grad.iter().zip(input.iter()).map(|(g, x)| g / x));
+            }
+            TensorOp::Relu(inner) => inner.add_grad(
+                grad.iter()
+                    .zip(data.iter())
+                    .map(|(&g, &y)| if y > 0.0 { g } else { 0.0 }),
+            ),
+            TensorOp::Sigmoid(inner) => {
+                inner.add_grad(grad.iter().zip(data.iter()).map(|(g, y)| g * y * (1.0 - y)))
+            }
+            TensorOp::Tanh(inner) => {
+                inner.add_grad(grad.iter().zip(data.iter()).map(|(g, y)| g * (1.0 - y * y)))
+            }
+            TensorOp::MatMul(lhs, rhs) => {
+                let (m, k, n) = (lhs.shape[0], lhs.shape[1], rhs.shape[1]);
+                let a = lhs.data.borrow();
+                let b = rhs.data.borrow();
+                // dA = dC * B^T and dB = A^T * dC.
+                let lhs_grad = matmul(&grad, &transpose(&b, k, n), m, n, k);
+                let rhs_grad = matmul(&transpose(&a, m, k), &grad, k, m, n);
+                lhs.add_grad(lhs_grad);
+                rhs.add_grad(rhs_grad);
+            }
+            TensorOp::Sum(inner) => inner.add_grad(std::iter::repeat_n(grad[0], inner.len())),
+            TensorOp::SumAxis(inner, axis) => {
+                let (outer, len, inner_len) = split_at_axis(&inner.shape, *axis);
+                let expanded = (0..outer).flat_map(|o| {
+                    let row = &grad[o * inner_len..][..inner_len];
+                    (0..len).flat_map(move |_| row.iter().copied())
+                });
+                inner.add_grad(expanded);
+            }
+            TensorOp::Reshape(inner) => inner.add_grad(grad.iter().copied()),
+        }
+    }
+
+    /// Sends `g * d out / d operand` to each operand, where `local(a, b)` gives both
+    /// partial derivatives and broadcast elements sum up.
+    fn propagate_binary(&self, lhs: &Tensor, rhs: &Tensor, local: impl Fn(f32, f32) -> (f32, f32)) {
+        let lhs_index = broadcast_indices(&lhs.shape, &self.shape);
+        let rhs_index = broadcast_indices(&rhs.shape, &self.shape);
+        let mut lhs_grad = vec![0.0; lhs.len()];
+        let mut rhs_grad = vec![0.0; rhs.len()];
+        {
+            let a = lhs.data.borrow();
+            let b = rhs.data.borrow();
+            for ((g, &i), &j) in self.grad.borrow().iter().zip(&lhs_index).zip(&rhs_index) {
+                let (da, db) = local(a[i], b[j]);
+                lhs_grad[i] += g * da;
+                rhs_grad[j] += g * db;
+            }
+        }
+        lhs.add_grad(lhs_grad);
+        rhs.add_grad(rhs_grad);
+    }
+
+    fn add_grad(&self, grad: impl IntoIterator<Item = f32>) {
+        for (sum, g) in self.grad.borrow_mut().iter_mut().zip(grad) {
+            *sum += g;
+        }
+    }
+
+    fn broadcast_op(
+        &self,
+        rhs: &Tensor,
+        f: impl Fn(f32, f32) -> f32,
+        op: fn(Tensor, Tensor) -> TensorOp,
+    ) -> Tensor {
+        let shape = broadcast_shape(&self.shape, &rhs.shape);
+        let lhs_index = broadcast_indices(&self.shape, &shape);
+        let rhs_index = broadcast_indices(&rhs.shape, &shape);
+        let a = self.data.borrow();
+        let b = rhs.data.borrow();
+        let data = lhs_index
+            .iter()
+            .zip(&rhs_index)
+            .map(|(&i, &j)| f(a[i], b[j]))
+            .collect();
+        Tensor::with_op(data, shape, op(self.clone(), rhs.clone()))
+    }
+}
+
+impl Drop for Tensor {
+    // Same as for `Value`: unlink operands owned only by this graph iteratively, so
+    // dropping a deep graph doesn't recurse once per node.
+    fn drop(&mut self) {
+        let mut pending = Vec::new();
+        if let Some(node) = Rc::get_mut(&mut self.0) {
+            pending.extend(std::mem::replace(&mut node.op, TensorOp::None).into_operands());
+        }
+        while let Some(mut tensor) = pending.pop() {
+            if let Some(node) = Rc::get_mut(&mut tensor.0) {
+                pending.extend(std::mem::replace(&mut node.op, TensorOp::None).into_operands());
+            }
+        }
+    }
+}
+
+impl Deref for Tensor {
+    type Target = Tensor_;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Tensor_ {
+    pub fn shape(&self) -> &[usize] {
+        &self.shape
+    }
+
+    pub fn len(&self) -> usize {
+        numel(&self.shape)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get_data(&self) -> Vec<f32> {
+        self.data.borrow().clone()
+    }
+
+    pub fn get_grad(&self) -> Vec<f32> {
+        self.grad.borrow().clone()
+    }
+
+    /// The only element of a single-element tensor.
+    pub fn item(&self) -> f32 {
+        assert_eq!(self.len(), 1, "item needs a single-element tensor");
+        self.data.borrow()[0]
+    }
+
+    pub fn set_data(&self, data: Vec<f32>) {
+        assert_eq!(data.len(), self.len(), "set_data must keep the shape");
+        *self.data.borrow_mut() = data;
+    }
+}
+
+impl From<f32> for Tensor {
+    fn from(data: f32) -> Self {
+        Tensor::scalar(data)
+    }
+}
+
+impl std::ops::Add for &Tensor {
+    type Output = Tensor;
+    fn add(self, rhs: Self) -> Tensor {
+        self.broadcast_op(rhs, |a, b| a + b, TensorOp::Add)
+    }
+}
+
+impl std::ops::Sub for &Tensor {
+    type Output = Tensor;
+    fn sub(self, rhs: Self) -> Tensor {
+        self.broadcast_op(rhs, |a, b| a - b, TensorOp::Sub)
+    }
+}
+
+/// Elementwise; see [`Tensor::matmul`] for the matrix product.
+impl std::ops::Mul for &Tensor {
+    type Output = Tensor;
+    fn mul(self, rhs: Self) -> Tensor {
+        self.broadcast_op(rhs, |a, b| a * b, TensorOp::Mul)
+    }
+}
+
+impl std::ops::Div for &Tensor {
+    type Output = Tensor;
+    fn div(self, rhs: Self) -> Tensor {
+        self.broadcast_op(rhs, |a, b| a / b, TensorOp::Div)
+    }
+}
+
+impl std::ops::Neg for &Tensor {
+    type Output = Tensor;
+    fn neg(self) -> Tensor {
+        self.map(|x| -x, TensorOp::Neg(self.clone()))
+    }
+}
+
+impl std::ops::Neg for Tensor {
+    type Output = Tensor;
+    fn neg(self) -> Tensor {
+        -&self
+    }
+}
+
+forward_binary_op!(Tensor, Add, add);
+forward_binary_op!(Tensor, Sub, sub);
+forward_binary_op!(Tensor, Mul, mul);
+forward_binary_op!(Tensor, Div, div);
+
+fn topological_order(root: &Tensor) -> Vec<Tensor> {
+    let mut order = Vec::new();
+    let mut visited = HashSet::new();
+    let mut stack = vec![(root.clone(), false)];
+    while let Some((tensor, expanded)) = stack.pop() {
+        if expanded {
+            order.push(tensor);
+            continue;
+        }
+        if !visited.insert(tensor.id) {
+            continue;
+        }
+        let operands: Vec<Tensor> = tensor.op.operands().into_iter().cloned().collect();
+        stack.push((tensor, true));
+        stack.extend(operands.into_iter().map(|operand| (operand, false)));
+    }
+    order
+}
+
+fn numel(shape: &[usize]) -> usize {
+    shape.iter().product()
+}
+
+/// Elements before, along and after `axis`.
+fn split_at_axis(shape: &[usize], axis: usize) -> (usize, usize, usize) {
+    (
+        numel(&shape[..axis]),
+        shape[axis],
+        numel(&shape[axis + 1..]),
+    )
+}
+
+fn broadcast_shape(lhs: &[usize], rhs: &[usize]) -> Vec<usize> {
+    let rank = lhs.len().max(rhs.len());
+    let (l, r) = (padded(lhs, rank), padded(rhs, rank));
+    l.iter()
+        .zip(&r)
+        .map(|(&a, &b)| match (a, b) {
+            _ if a == b => a,
+            (1, _) => b,
+            (_, 1) => a,
+            _ => panic!("cannot broadcast shapes {lhs:?} and {rhs:?}"),
+        })
+        .collect()
+}
+
+fn padded(shape: &[usize], rank: usize) -> Vec<usize> {
+    let mut padded = vec![1; rank - shape.len()];
+    padded.extend_from_slice(shape);
+    padded
+}
+
+/// For every element of `out_shape`, the index of the element of `shape` that is
+/// broadcast to it.
+fn broadcast_indices(shape: &[usize], out_shape: &[usize]) -> Vec<usize> {
+    let rank = out_shape.len();
+    let shape = padded(shape, rank);
+    let mut strides = vec![0; rank];
+    let mut stride = 1;
+    for axis in (0..rank).rev() {
+        if shape[axis] != 1 {
+            strides[axis] = stride;
+        }
+        stride *= shape[axis];
+    }
+
+    let mut indices = Vec::with_capacity(numel(out_shape));
+    let mut position = vec![0; rank];
+    for _ in 0..numel(out_shape) {
+        indices.push(position.iter().zip(&strides).map(|(p, s)| p * s).sum());
+        for axis in (0..rank).rev() {
+            position[axis] += 1;
+            if position[axis] < out_shape[axis] {
+                break;
+            }
+            position[axis] = 0;
+        }
+    }
+    indices
+}
+
+fn matmul(a: &[f32], b: &[f32], m: usize, k: usize, n: usize) -> Vec<f32> {
+    let mut out = vec![0.0; m * n];
+    for i in 0..m {
+        for p in 0..k {
+            let x = a[i * k + p];
+            for (o, y) in out[i * n..][..n].iter_mut().zip(&b[p * n..][..n]) {
+                *o += x * y;
+            }
+        }
+    }
+    out
+}
+
+fn transpose(a: &[f32], rows: usize, cols: usize) -> Vec<f32> {
+    let mut out = vec![0.0; a.len()];
+    for i in 0..rows {
+        for j in 0..cols {
+            out[j * rows + i] = a[i * cols + j];
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng, rngs::StdRng};
+
+    use super::*;
+
+    const EPS: f32 = 1e-2;
+
+    /// Compares the gradients of `sum(f(inputs) * weights)` against central
+    /// differences, with fixed uneven weights so every output element matters.
+    fn check_gradients(f: impl Fn(&[Tensor]) -> Tensor, inputs: &[(&[f32], &[usize])]) {
+        let loss = |tensors: &[Tensor]| {
+            let out = f(tensors);
+            let weights: Vec<f32> = (0..out.len()).map(|i| 0.5 + 0.25 * i as f32).collect();
+            (&out * &Tensor::new(weights, out.shape())).sum()
+        };
+        let tensors: Vec<Tensor> = inputs
+            .iter()
+            .map(|(data, shape)| Tensor::new(data.to_vec(), shape))
+            .collect();
+        loss(&tensors).backward();
+
+        for (t, tensor) in tensors.iter().enumerate() {
+            let analytic = tensor.get_grad();
+            for (i, analytic) in analytic.into_iter().enumerate() {
+                let eval = |delta: f32| {
+                    let shifted: Vec<Tensor> = inputs
+                        .iter()
+                        .enumerate()
+                        .map(|(u, (data, shape))| {
+                            let mut data = data.to_vec();
+                            if u == t {
+                                data[i] += delta;
+                            }
+                            Tensor::new(data, shape)
+                        })
+                        .collect();
+                    loss(&shifted).item()
+                };
+                let numeric = (eval(EPS) - eval(-EPS)) / (2.0 * EPS);
+                assert!(
+                    (numeric - analytic).abs() < 1e-2 * (1.0 + numeric.abs()),
+                    "input {t}[{i}]: numeric {numeric} analytic {analytic}"
+                );
+            }
+        }
+    }
+
+    fn assert_close(actual: &[f32], expected: &[f32]) {
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-5, "{actual:?} != {expected:?}");
+        }
+    }
+
+    const A: &[f32] = &[0.5, -1.0, 1.5, 2.0, -0.5, 0.3];
+    const B: &[f32] = &[1.2, -0.7, 0.9];
+
+    #[test]
+    fn broadcasting_shapes() {
+        assert_eq!(broadcast_shape(&[2, 3], &[3]), [2, 3]);
+        assert_eq!(broadcast_shape(&[2, 1], &[1, 3]), [2, 3]);
+        assert_eq!(broadcast_shape(&[], &[4]), [4]);
+        assert_eq!(broadcast_indices(&[3], &[2, 3]), [0, 1, 2, 0, 1, 2]);
+        assert_eq!(broadcast_indices(&[2, 1], &[2, 3]), [0, 0, 0, 1, 1, 1]);
+
+        let sum = Tensor::new(A.to_vec(), &[2, 3]) + Tensor::new(vec![10.0, 20.0], &[2, 1]);
+        assert_close(&sum.get_data(), &[10.5, 9.0, 11.5, 22.0, 19.5, 20.3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot broadcast")]
+    fn incompatible_broadcast() {
+        let _ = Tensor::zeros(&[2, 3]) + Tensor::zeros(&[2]);
+    }
+
+    #[test]
+    fn elementwise_gradients() {
+        check_gradients(|t| &t[0] + &t[1], &[(A, &[2, 3]), (B, &[3])]);
+        check_gradients(|t| &t[0] - &t[1], &[(&A[..2], &[2, 1]), (A, &[2, 3])]);
+        check_gradients(|t| &t[0] * &t[1], &[(A, &[2, 3]), (B, &[3])]);
+        check_gradients(|t| &t[0] / &t[1], &[(A, &[2, 3]), (B, &[3])]);
+        check_gradients(|t| 2.0 - &t[0] * &t[0], &[(A, &[6])]);
+        check_gradients(|t| -&t[0], &[(A, &[6])]);
+    }
+
+    #[test]
+    fn unary_gradients() {
+        check_gradients(|t| t[0].tanh(), &[(A, &[2, 3])]);
+        check_gradients(|t| t[0].sigmoid(), &[(A, &[2, 3])]);
+        check_gradients(|t| t[0].relu(), &[(A, &[2, 3])]);
+        check_gradients(|t| t[0].exp(), &[(A, &[2, 3])]);
+        check_gradients(|t| t[0].pow(2.0).log(), &[(A, &[2, 3])]);
+        check_gradients(|t| (t[0].pow(2.0) + 1.0).pow(0.5), &[(A, &[2, 3])]);
+    }
+
+    #[test]
+    fn matmul_gradients() {
+        let c = Tensor::new(A.to_vec(), &[2, 3]).matmul(&Tensor::new(A.to_vec(), &[3, 2]));
+        assert_eq!(c.shape(), [2, 2]);
+        assert_close(&c.get_data(), &[-2.0, -2.05, 0.1, -2.91]);
+
+        check_gradients(|t| t[0].matmul(&t[1]), &[(A, &[2, 3]), (A, &[3, 2])]);
+        check_gradients(|t| t[0].matmul(&t[0]), &[(&A[..4], &[2, 2])]);
+    }
+
+    #[test]
+    fn reduction_and_reshape_gradients() {
+        let t = Tensor::new(A.to_vec(), &[2, 3]);
+        assert_close(&t.sum_axis(0).get_data(), &[2.5, -1.5, 1.8]);
+        assert_close(&t.sum_axis(1).get_data(), &[1.0, 1.8]);
+        assert_close(
+            &t.reshape(&[3, 2]).sum_axis(1).get_data(),
+            &[-0.5, 3.5, -0.2],
+        );
+        assert!((t.mean().item() - 2.8 / 6.0).abs() < 1e-6);
+
+        check_gradients(|t| t[0].sum_axis(0), &[(A, &[2, 3])]);
+        check_gradients(|t| t[0].sum_axis(1), &[(A, &[2, 3])]);
+        check_gradients(|t| t[0].mean_axis(1), &[(A, &[3, 2])]);
+        check_gradients(
+            |t| t[0].reshape(&[3, 2]).matmul(&t[1]),
+            &[(A, &[6]), (&B[..2], &[2, 1])],
+        );
+        check_gradients(|t| t[0].mean(), &[(A, &[2, 3])]);
+    }
+
+    #[test]
+    fn small_network_learns_xor() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut random = |shape: &[usize]| {
+            let data = (0..numel(shape))
+                .map(|_| rng.random_range(-1.0..1.0))
+                .collect();
+            Tensor::new(data, shape)
+        };
+        let (w1, b1, w2, b2) = (random(&[2, 8]), random(&[8]), random(&[8, 1]), random(&[1]));
+        let inputs = Tensor::new(vec![0.0, 0.0, 0.0, 1.0, 1.0, 0.0, 1.0, 1.0], &[4, 2]);
+        let targets = Tensor::new(vec![0.0, 1.0, 1.0, 0.0], &[4, 1]);
+
+        let loss = || {
+            let hidden = (inputs.matmul(&w1) + &b1).tanh();
+            let outputs = (hidden.matmul(&w2) + &b2).sigmoid();
+            (outputs - &targets).pow(2.0).mean()
+        };
+        let first = loss().item();
+        for _ in 0..500 {
+            let loss = loss();
+            loss.backward();
+            for p in [&w1, &b1, &w2, &b2] {
+                let updated = p
+                    .get_data()
+                    .iter()
+                    .zip(p.get_grad())
+                    .map(|(x, g)| x - 2.0 * g)
+                    .collect();
+                p.set_data(updated);
+            }
+        }
+        let last = loss().item();
+
+        assert!(last < 0.05, "loss went from {first} to {last}");
+    }
+
+    #[test]
+    fn deep_chain() {
+        let x = Tensor::new(vec![0.5, -0.5], &[2]);
+        let mut chain = x.clone();
+        for _ in 0..200_000 {
+            chain = &chain * 1.0;
+        }
+        let out = chain.sum();
+
+        out.backward();
+        assert_eq!(x.get_grad(), [1.0, 1.0]);
+
+        drop(out);
+        drop(chain);
+    }
+}
@@ -0,0 +1,26 @@
+use video_2_ai_fc::{config::OaConfig, conv, oa_client::new_recording_client, tools::new_ai_tools};
+
+/// Records a conversation into a fixture usable by the replay tests:
+///
+/// cargo run --example record_example -- "Convert 3 Dollars to Euro" tests/fixtures/my_case.json
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv::dotenv()?;
+    let mut args = std::env::args().skip(1);
+    let question = args.next().ok_or("Missing question argument")?;
+    let fixture_path = args.next().ok_or("Missing fixture path argument")?;
+
+    let oa_client = new_recording_client(OaConfig::from_env()?)?;
+    let ai_tools = new_ai_tools()?;
+    let model = oa_client.default_model().to_string();
+
+    let response = conv::send_user_msg(oa_client.clone(), ai_tools, &model, &question).await?;
+    println!("Question: {question}\nResponse: {response}");
+
+    if let Some(recorder) = oa_client.recorder() {
+        recorder.save(&fixture_path)?;
+        println!("Saved fixture to {fixture_path}");
+    }
+
+    Ok(())
+}
@@ -1,36 +1,39 @@
-use tokio::task::JoinSet;
-use video_2_ai_fc::Error;
-use video_2_ai_fc::{conv, oa_client::new_oa_client, tools::new_ai_tools};
+use video_2_ai_fc::batch::{self, BatchConfig};
+use video_2_ai_fc::{oa_client::new_oa_client, tools::new_ai_tools};
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     dotenv::dotenv()?;
     let oa_client = new_oa_client()?;
     let ai_tools = new_ai_tools()?;
+    let model = oa_client.default_model().to_string();
 
-    let questions = &[
-        "Convert 3 Dollars to Euro",
-        "why is the sky red (be concise)",
-        "Convert 5 Euro to Hryvnia",
+    let questions = vec![
+        "Convert 3 Dollars to Euro".to_string(),
+        "why is the sky red (be concise)".to_string(),
+        "Convert 5 Euro to Hryvnia".to_string(),
     ];
 
-    let mut join_set: JoinSet<(String, Result<String, Error>)> = JoinSet::new();
+    let config = BatchConfig {
+        max_concurrency: 2,
+        requests_per_minute: Some(30),
+        ..Default::default()
+    };
 
-    for &q in questions {
-        let oa_client = oa_client.clone();
-        let ai_tools = ai_tools.clone();
+    let items = batch::run_batch(oa_client, ai_tools, &model, questions, config).await?;
 
-        join_set.spawn(async move {
-            let response = conv::send_user_msg(oa_client.clone(), ai_tools.clone(), q).await;
-            (q.to_string(), response)
-        });
+    for item in &items {
+        let response = item.answer.as_deref().or(item.error.as_deref());
+        println!(
+            "Question: {}\nResponse: {}\n({} ms, {} tokens)\n\n",
+            item.question,
+            response.unwrap_or_default(),
+            item.latency_ms,
+            item.usage.total_tokens
+        );
     }
 
-    while let Some(join_res) = join_set.join_next().await {
-        let (question, result) = join_res?;
-        let result = result?;
-
-        println!("Question: {question}\nResponse: {result}\n\n");
-    }
+    batch::write_report_jsonl("batch_report.jsonl", &items)?;
 
     Ok(())
 }
@@ -0,0 +1,35 @@
+use futures::StreamExt;
+use std::io::Write;
+use video_2_ai_fc::stream::ConvEvent;
+use video_2_ai_fc::{conv, oa_client::new_oa_client, tools::new_ai_tools};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv::dotenv()?;
+    let oa_client = new_oa_client()?;
+    let ai_tools = new_ai_tools()?;
+    let model = oa_client.default_model().to_string();
+
+    let question = "Convert 3 Dollars to Euro, then explain the result in one sentence";
+    println!("Question: {question}");
+
+    let mut events = std::pin::pin!(conv::send_user_msg_stream(
+        oa_client, ai_tools, &model, question
+    ));
+
+    while let Some(event) = events.next().await {
+        match event? {
+            ConvEvent::TextDelta(delta) => {
+                print!("{delta}");
+                std::io::stdout().flush()?;
+            }
+            ConvEvent::ToolCallStarted { fn_name, .. } => println!("-> calling {fn_name}"),
+            ConvEvent::ToolResult {
+                fn_name, response, ..
+            } => println!("<- {fn_name}: {response}"),
+            ConvEvent::Done { .. } => println!(),
+        }
+    }
+
+    Ok(())
+}
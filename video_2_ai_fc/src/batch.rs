@@ -0,0 +1,238 @@
+use std::{
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::Path,
+    sync::{
+        Arc,
+        atomic::{AtomicU32, Ordering},
+    },
+    time::Duration,
+};
+
+use async_openai::types::CreateChatCompletionRequest;
+use serde::{Deserialize, Serialize};
+use tokio::{
+    sync::{Mutex, Semaphore},
+    task::JoinSet,
+    time::Instant,
+};
+
+use crate::{
+    Result,
+    conv::{self, TokenUsage, ToolCallRecord},
+    oa_client::OaClient,
+    tools::AiTools,
+};
+
+#[derive(Debug, Clone)]
+pub struct BatchConfig {
+    pub max_concurrency: usize,
+    /// Chat requests sent per minute, across all conversations and retries included.
+    /// `None` for no limit.
+    pub requests_per_minute: Option<u32>,
+    /// Retries of a single chat request failing with a transient error.
+    /// Tools already run are not run again.
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled on every following one.
+    pub retry_backoff: Duration,
+}
+
+impl Default for BatchConfig {
+    fn default() -> Self {
+        BatchConfig {
+            max_concurrency: 4,
+            requests_per_minute: None,
+            max_retries: 2,
+            retry_backoff: Duration::from_millis(500),
+        }
+    }
+}
+
+/// One line of the JSONL report.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchItem {
+    pub question: String,
+    pub answer: Option<String>,
+    pub error: Option<String>,
+    pub tool_calls: Vec<ToolCallRecord>,
+    pub latency_ms: u64,
+    pub usage: TokenUsage,
+    /// Chat requests sent for the question, retries included.
+    pub attempts: u32,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PromptLine {
+    Object { question: String },
+    Text(String),
+}
+
+/// Reads prompts from a JSONL file, each line being `{"question": "..."}` or a JSON string.
+pub fn load_prompts_jsonl(path: impl AsRef<Path>) -> Result<Vec<String>> {
+    let file = std::fs::File::open(path)?;
+    let mut prompts = Vec::new();
+
+    for (idx, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let prompt: PromptLine = serde_json::from_str(&line)
+            .map_err(|err| format!("Invalid prompt on line {}: {err}", idx + 1))?;
+        prompts.push(match prompt {
+            PromptLine::Object { question } | PromptLine::Text(question) => question,
+        });
+    }
+
+    Ok(prompts)
+}
+
+pub fn write_report_jsonl(path: impl AsRef<Path>, items: &[BatchItem]) -> Result<()> {
+    let mut writer = BufWriter::new(std::fs::File::create(path)?);
+    for item in items {
+        serde_json::to_writer(&mut writer, item)?;
+        writer.write_all(b"\n")?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Runs every question like [`conv::send_user_msg_detailed`], rate limiting and
+/// retrying each chat request on its own.
+///
+/// Items come back in the order of `questions`; a failed question is reported in
+/// its item rather than failing the batch.
+pub async fn run_batch(
+    oa_client: OaClient,
+    ai_tools: AiTools,
+    model: &str,
+    questions: Vec<String>,
+    config: BatchConfig,
+) -> Result<Vec<BatchItem>> {
+    let semaphore = Arc::new(Semaphore::new(config.max_concurrency.max(1)));
+    let rate_limiter = Arc::new(RateLimiter::new(config.requests_per_minute));
+    let config = Arc::new(config);
+    let model: Arc<str> = model.into();
+
+    let mut join_set = JoinSet::new();
+
+    for (idx, question) in questions.into_iter().enumerate() {
+        let oa_client = oa_client.clone();
+        let ai_tools = ai_tools.clone();
+        let semaphore = semaphore.clone();
+        let rate_limiter = rate_limiter.clone();
+        let config = config.clone();
+        let model = model.clone();
+
+        join_set.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            let item = run_item(
+                oa_client,
+                ai_tools,
+                &model,
+                question,
+                &config,
+                &rate_limiter,
+            )
+            .await;
+            (idx, item)
+        });
+    }
+
+    let mut items: Vec<(usize, BatchItem)> = Vec::with_capacity(join_set.len());
+    while let Some(join_res) = join_set.join_next().await {
+        items.push(join_res.map_err(|err| format!("Batch task failed: {err}"))?);
+    }
+    items.sort_by_key(|(idx, _)| *idx);
+
+    Ok(items.into_iter().map(|(_, item)| item).collect())
+}
+
+async fn run_item(
+    oa_client: OaClient,
+    ai_tools: AiTools,
+    model: &str,
+    question: String,
+    config: &BatchConfig,
+    rate_limiter: &RateLimiter,
+) -> BatchItem {
+    let start = Instant::now();
+    let attempts = AtomicU32::new(0);
+
+    let create_chat = |msg_req: CreateChatCompletionRequest| {
+        let (oa_client, attempts) = (&oa_client, &attempts);
+        async move {
+            let mut retries = 0;
+            loop {
+                rate_limiter.acquire().await;
+                attempts.fetch_add(1, Ordering::Relaxed);
+
+                match oa_client.create_chat(msg_req.clone()).await {
+                    Err(err) if err.is_transient() && retries < config.max_retries => {
+                        tokio::time::sleep(config.retry_backoff * 2u32.pow(retries)).await;
+                        retries += 1;
+                    }
+                    res => break res,
+                }
+            }
+        }
+    };
+    let temperature = oa_client.temperature();
+    let res = conv::send_user_msg_via(ai_tools, model, temperature, &question, create_chat).await;
+
+    let latency_ms = start.elapsed().as_millis() as u64;
+    let attempts = attempts.into_inner();
+
+    match res {
+        Ok(outcome) => BatchItem {
+            question,
+            answer: Some(outcome.content),
+            error: None,
+            tool_calls: outcome.tool_calls,
+            latency_ms,
+            usage: outcome.usage,
+            attempts,
+        },
+        Err(err) => BatchItem {
+            question,
+            answer: None,
+            error: Some(err.to_string()),
+            tool_calls: vec![],
+            latency_ms,
+            usage: TokenUsage::default(),
+            attempts,
+        },
+    }
+}
+
+/// Spaces acquisitions evenly so no more than `per_minute` happen in a minute.
+struct RateLimiter {
+    interval: Option<Duration>,
+    next_slot: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    fn new(per_minute: Option<u32>) -> Self {
+        RateLimiter {
+            interval: per_minute
+                .filter(|&rpm| rpm > 0)
+                .map(|rpm| Duration::from_secs(60) / rpm),
+            next_slot: Mutex::new(Instant::now()),
+        }
+    }
+
+    async fn acquire(&self) {
+        let Some(interval) = self.interval else {
+            return;
+        };
+
+        let slot = {
+            let mut next_slot = self.next_slot.lock().await;
+            let slot = (*next_slot).max(Instant::now());
+            *next_slot = slot + interval;
+            slot
+        };
+
+        tokio::time::sleep_until(slot).await;
+    }
+}
@@ -0,0 +1,115 @@
+use std::{path::Path, str::FromStr, time::Duration};
+
+use serde::Deserialize;
+
+use crate::{Result, gpts};
+
+pub const ENV_BASE_URL: &str = "OA_BASE_URL";
+pub const ENV_API_KEY_ENV: &str = "OA_API_KEY_ENV";
+pub const ENV_MODEL: &str = "OA_MODEL";
+pub const ENV_TEMPERATURE: &str = "OA_TEMPERATURE";
+pub const ENV_TIMEOUT_SECS: &str = "OA_TIMEOUT_SECS";
+pub const ENV_CONNECT_TIMEOUT_SECS: &str = "OA_CONNECT_TIMEOUT_SECS";
+
+pub const OPENAI_BASE_URL: &str = "https://api.openai.com/v1";
+pub const OLLAMA_BASE_URL: &str = "http://localhost:11434/v1";
+pub const DEFAULT_API_KEY_ENV: &str = "OPENAI_API_KEY";
+
+/// Settings for any OpenAI-compatible chat completion server.
+///
+/// Missing fields fall back to [`OaConfig::default`], which targets the OpenAI API.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct OaConfig {
+    pub base_url: String,
+    /// Name of the env variable holding the API key (not the key itself).
+    pub api_key_env: String,
+    pub model: String,
+    pub temperature: Option<f32>,
+    pub timeout_secs: Option<u64>,
+    pub connect_timeout_secs: Option<u64>,
+}
+
+impl Default for OaConfig {
+    fn default() -> Self {
+        OaConfig {
+            base_url: OPENAI_BASE_URL.to_string(),
+            api_key_env: DEFAULT_API_KEY_ENV.to_string(),
+            model: gpts::MODEL_3_TURBO.to_string(),
+            temperature: None,
+            timeout_secs: None,
+            connect_timeout_secs: None,
+        }
+    }
+}
+
+impl OaConfig {
+    /// Preset for a local Ollama server exposing its OpenAI-compatible API.
+    pub fn ollama(model: impl Into<String>) -> Self {
+        OaConfig {
+            base_url: OLLAMA_BASE_URL.to_string(),
+            model: model.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Defaults overridden by the `OA_*` env variables that are set.
+    pub fn from_env() -> Result<Self> {
+        OaConfig::default().with_env_overrides()
+    }
+
+    /// Loads a JSON config file, then applies the `OA_*` env overrides on top.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        let config: OaConfig = serde_json::from_str(&content)?;
+        config.with_env_overrides()
+    }
+
+    pub fn with_env_overrides(mut self) -> Result<Self> {
+        if let Some(base_url) = env_var(ENV_BASE_URL) {
+            self.base_url = base_url;
+        }
+        if let Some(api_key_env) = env_var(ENV_API_KEY_ENV) {
+            self.api_key_env = api_key_env;
+        }
+        if let Some(model) = env_var(ENV_MODEL) {
+            self.model = model;
+        }
+        if let Some(temperature) = env_parse(ENV_TEMPERATURE)? {
+            self.temperature = Some(temperature);
+        }
+        if let Some(timeout_secs) = env_parse(ENV_TIMEOUT_SECS)? {
+            self.timeout_secs = Some(timeout_secs);
+        }
+        if let Some(connect_timeout_secs) = env_parse(ENV_CONNECT_TIMEOUT_SECS)? {
+            self.connect_timeout_secs = Some(connect_timeout_secs);
+        }
+        Ok(self)
+    }
+
+    /// The API key read from `api_key_env`; empty when unset, which local servers accept.
+    pub fn api_key(&self) -> String {
+        env_var(&self.api_key_env).unwrap_or_default()
+    }
+
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout_secs.map(Duration::from_secs)
+    }
+
+    pub fn connect_timeout(&self) -> Option<Duration> {
+        self.connect_timeout_secs.map(Duration::from_secs)
+    }
+}
+
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|v| !v.is_empty())
+}
+
+fn env_parse<T: FromStr>(name: &str) -> Result<Option<T>> {
+    env_var(name)
+        .map(|raw| {
+            raw.parse()
+                .map_err(|_| format!("Invalid value '{raw}' for env '{name}'").into())
+        })
+        .transpose()
+}
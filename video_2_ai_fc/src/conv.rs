@@ -1,92 +1,318 @@
-use async_openai::types::{ChatCompletionToolChoiceOption, CreateChatCompletionRequest};
+use async_openai::types::{
+    ChatCompletionMessageToolCall, ChatCompletionRequestMessage,
+    ChatCompletionRequestToolMessageContent, ChatCompletionTool, ChatCompletionToolChoiceOption,
+    CompletionUsage, CreateChatCompletionRequest, CreateChatCompletionResponse,
+};
+use futures::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use tokio::sync::mpsc;
 
 use crate::{
     chat::{self},
     error::Result,
-    gpts,
     oa_client::OaClient,
+    stream::{ConvEvent, ToolCallAssembler},
     tools::AiTools,
 };
 
+const MAX_TOOL_ROUNDS: usize = 8;
+
 pub async fn send_user_msg(
     oa_client: OaClient,
     ai_tools: AiTools,
+    model: &str,
     question: &str,
 ) -> Result<String> {
-    let chat_client = oa_client.chat();
-    let model = gpts::MODEL;
+    let outcome = send_user_msg_detailed(oa_client, ai_tools, model, question).await?;
+    Ok(outcome.content)
+}
 
-    let messages = vec![chat::user_msg(question)?];
+/// Final answer of a conversation along with what it took to get there.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConvOutcome {
+    pub content: String,
+    pub tool_calls: Vec<ToolCallRecord>,
+    pub usage: TokenUsage,
+}
 
-    let rpc_router = ai_tools.router();
-    let tools = Some(ai_tools.chat_tools_clone());
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TokenUsage {
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+    pub total_tokens: u32,
+}
 
-    let msg_req = CreateChatCompletionRequest {
-        model: gpts::MODEL.to_string(),
-        messages: messages.clone(),
-        tools: tools.clone(),
-        tool_choice: Some(ChatCompletionToolChoiceOption::Auto),
-        ..Default::default()
+impl TokenUsage {
+    fn add(&mut self, usage: Option<&CompletionUsage>) {
+        if let Some(usage) = usage {
+            self.prompt_tokens += usage.prompt_tokens;
+            self.completion_tokens += usage.completion_tokens;
+            self.total_tokens += usage.total_tokens;
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolCallRecord {
+    pub fn_name: String,
+    pub arguments: Value,
+    pub response: Option<Value>,
+}
+
+/// Pairs the assistant tool calls of a conversation with the matching tool messages.
+pub fn tool_calls(messages: &[ChatCompletionRequestMessage]) -> Vec<ToolCallRecord> {
+    let tool_response = |tool_call_id: &str| {
+        messages.iter().find_map(|msg| match msg {
+            ChatCompletionRequestMessage::Tool(tool_msg)
+                if tool_msg.tool_call_id == tool_call_id =>
+            {
+                match &tool_msg.content {
+                    ChatCompletionRequestToolMessageContent::Text(text) => {
+                        Some(serde_json::from_str(text).unwrap_or(Value::String(text.clone())))
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        })
     };
 
-    let chat_response = chat_client.create(msg_req).await?;
-    let first_choise = chat::first_chiose(chat_response)?;
+    messages
+        .iter()
+        .filter_map(|msg| match msg {
+            ChatCompletionRequestMessage::Assistant(asst_msg) => asst_msg.tool_calls.as_ref(),
+            _ => None,
+        })
+        .flatten()
+        .map(|tool_call| ToolCallRecord {
+            fn_name: tool_call.function.name.clone(),
+            arguments: serde_json::from_str(&tool_call.function.arguments)
+                .unwrap_or(Value::String(tool_call.function.arguments.clone())),
+            response: tool_response(&tool_call.id),
+        })
+        .collect()
+}
 
-    if let Some(response_content) = first_choise.message.content {
-        return Ok(response_content);
-    }
+/// Same as [`send_user_msg`], also reporting the tool calls made and the tokens used.
+pub async fn send_user_msg_detailed(
+    oa_client: OaClient,
+    ai_tools: AiTools,
+    model: &str,
+    question: &str,
+) -> Result<ConvOutcome> {
+    let temperature = oa_client.temperature();
+    let create_chat = |msg_req| oa_client.create_chat(msg_req);
+    send_user_msg_via(ai_tools, model, temperature, question, create_chat).await
+}
 
-    struct ToolResponse {
-        tool_call_id: String,
-        response: Value,
-    }
+/// Runs the conversation, sending every chat request through `create_chat`.
+///
+/// Lets the caller wrap each request on its own (rate limiting, retries) without
+/// running the tools again.
+pub(crate) async fn send_user_msg_via<C, F>(
+    ai_tools: AiTools,
+    model: &str,
+    temperature: Option<f32>,
+    question: &str,
+    create_chat: C,
+) -> Result<ConvOutcome>
+where
+    C: Fn(CreateChatCompletionRequest) -> F,
+    F: Future<Output = Result<CreateChatCompletionResponse>>,
+{
+    let tools = ai_tools.chat_tools_clone();
+    let (tools, create_chat) = (&tools, &create_chat);
+
+    let next_turn = |messages| async move {
+        let msg_req = CreateChatCompletionRequest {
+            model: model.to_string(),
+            messages,
+            tools: Some(tools.clone()),
+            tool_choice: Some(ChatCompletionToolChoiceOption::Auto),
+            temperature,
+            ..Default::default()
+        };
 
-    let mut tool_responses: Vec<ToolResponse> = vec![];
-    let tool_calls = first_choise.message.tool_calls;
+        let chat_response = create_chat(msg_req).await?;
+        let usage = chat_response.usage.clone();
+        let message = chat::first_chiose(chat_response)?.message;
 
-    for tool_call in tool_calls.iter().flatten() {
-        let tool_call_id = tool_call.id.clone();
-        let fn_name = tool_call.function.name.clone();
-        let params: Value = serde_json::from_str(&tool_call.function.arguments)?;
+        Ok(Turn {
+            content: message.content,
+            tool_calls: message.tool_calls.unwrap_or_default(),
+            usage,
+        })
+    };
 
-        let call_result = rpc_router
-            .call_route(None, fn_name, Some(params))
-            .await
-            .map_err(Box::new)?;
-        let response = call_result.value;
+    run_tool_rounds(&ai_tools, question, next_turn, None).await
+}
 
-        tool_responses.push(ToolResponse {
-            tool_call_id,
-            response,
-        });
-    }
+/// What the model answered in one round.
+struct Turn {
+    content: Option<String>,
+    tool_calls: Vec<ChatCompletionMessageToolCall>,
+    usage: Option<CompletionUsage>,
+}
 
-    let mut messages = messages;
-    if let Some(tool_calls) = tool_calls {
-        messages.push(chat::tool_calls_msg(tool_calls)?);
-    }
+/// The tool-round loop behind both the streaming and the non-streaming conversation.
+///
+/// Asks `next_turn` for an answer to the messages so far, runs the tools it calls and
+/// asks again, until an answer calls no tool or [`MAX_TOOL_ROUNDS`] is reached. Tool
+/// results are also sent to `events`, when given.
+async fn run_tool_rounds<T, F>(
+    ai_tools: &AiTools,
+    question: &str,
+    next_turn: T,
+    events: Option<&mpsc::Sender<Result<ConvEvent>>>,
+) -> Result<ConvOutcome>
+where
+    T: Fn(Vec<ChatCompletionRequestMessage>) -> F,
+    F: Future<Output = Result<Turn>>,
+{
+    let mut messages = vec![chat::user_msg(question)?];
+    let mut usage = TokenUsage::default();
+
+    for _ in 0..MAX_TOOL_ROUNDS {
+        let turn = next_turn(messages.clone()).await?;
+        usage.add(turn.usage.as_ref());
 
-    for ToolResponse {
-        tool_call_id,
-        response,
-    } in tool_responses
-    {
-        messages.push(chat::tool_response_msg(tool_call_id, response)?);
+        if turn.tool_calls.is_empty() {
+            return Ok(ConvOutcome {
+                content: turn.content.ok_or("No final content")?,
+                tool_calls: tool_calls(&messages),
+                usage,
+            });
+        }
+
+        let mut tool_responses = Vec::with_capacity(turn.tool_calls.len());
+        for tool_call in &turn.tool_calls {
+            let response = ai_tools.call(tool_call).await?;
+            if let Some(tx) = events {
+                let event = ConvEvent::ToolResult {
+                    tool_call_id: tool_call.id.clone(),
+                    fn_name: tool_call.function.name.clone(),
+                    response: response.clone(),
+                };
+                send_event(tx, event).await?;
+            }
+            tool_responses.push(chat::tool_response_msg(tool_call.id.clone(), response)?);
+        }
+
+        messages.push(chat::tool_calls_msg(turn.tool_calls)?);
+        messages.extend(tool_responses);
     }
 
-    let msg_req = CreateChatCompletionRequest {
+    Err(format!("No final content after {MAX_TOOL_ROUNDS} tool rounds").into())
+}
+
+/// Streaming variant of [`send_user_msg`].
+///
+/// Tool calls are assembled from the streamed fragments, executed once the model
+/// finishes the turn, and the follow-up answer is streamed in turn. The stream ends
+/// after [`ConvEvent::Done`] or the first error.
+pub fn send_user_msg_stream(
+    oa_client: OaClient,
+    ai_tools: AiTools,
+    model: &str,
+    question: &str,
+) -> impl Stream<Item = Result<ConvEvent>> + Send + 'static {
+    let (tx, mut rx) = mpsc::channel(32);
+    let model = model.to_string();
+    let question = question.to_string();
+
+    tokio::spawn(async move {
+        let res = run_stream(&oa_client, &ai_tools, &model, &question, &tx).await;
+        if let Err(err) = res {
+            let _ = tx.send(Err(err)).await;
+        }
+    });
+
+    futures::stream::poll_fn(move |cx| rx.poll_recv(cx))
+}
+
+async fn run_stream(
+    oa_client: &OaClient,
+    ai_tools: &AiTools,
+    model: &str,
+    question: &str,
+    tx: &mpsc::Sender<Result<ConvEvent>>,
+) -> Result<()> {
+    let tools = ai_tools.chat_tools_clone();
+    let tools = &tools;
+
+    let next_turn = |messages| async move {
+        let msg_req = stream_request(oa_client, model, messages, tools.clone());
+        let mut chat_stream = oa_client.create_chat_stream(msg_req).await?;
+
+        let mut content = String::new();
+        let mut assembler = ToolCallAssembler::default();
+
+        while let Some(chunk) = chat_stream.next().await {
+            let Some(choice) = chunk?.choices.into_iter().next() else {
+                continue;
+            };
+
+            if let Some(delta) = choice.delta.content.filter(|d| !d.is_empty()) {
+                content.push_str(&delta);
+                send_event(tx, ConvEvent::TextDelta(delta)).await?;
+            }
+
+            for tool_chunk in choice.delta.tool_calls.into_iter().flatten() {
+                if let Some((tool_call_id, fn_name)) = assembler.push(tool_chunk) {
+                    let event = ConvEvent::ToolCallStarted {
+                        tool_call_id,
+                        fn_name,
+                    };
+                    send_event(tx, event).await?;
+                }
+            }
+        }
+
+        for (tool_call_id, fn_name) in assembler.flush() {
+            let event = ConvEvent::ToolCallStarted {
+                tool_call_id,
+                fn_name,
+            };
+            send_event(tx, event).await?;
+        }
+
+        Ok(Turn {
+            content: Some(content),
+            tool_calls: assembler.finish(),
+            usage: None,
+        })
+    };
+
+    let outcome = run_tool_rounds(ai_tools, question, next_turn, Some(tx)).await?;
+    send_event(
+        tx,
+        ConvEvent::Done {
+            content: outcome.content,
+        },
+    )
+    .await
+}
+
+fn stream_request(
+    oa_client: &OaClient,
+    model: &str,
+    messages: Vec<ChatCompletionRequestMessage>,
+    tools: Vec<ChatCompletionTool>,
+) -> CreateChatCompletionRequest {
+    CreateChatCompletionRequest {
         model: model.to_string(),
         messages,
-        tools,
+        tools: Some(tools),
         tool_choice: Some(ChatCompletionToolChoiceOption::Auto),
+        temperature: oa_client.temperature(),
+        stream: Some(true),
         ..Default::default()
-    };
-
-    let chat_response = chat_client.create(msg_req).await?;
-    let first_choise = chat::first_chiose(chat_response)?;
-
-    let content = first_choise.message.content.ok_or("No final content")?;
+    }
+}
 
-    Ok(content)
+async fn send_event(tx: &mpsc::Sender<Result<ConvEvent>>, event: ConvEvent) -> Result<()> {
+    tx.send(Ok(event))
+        .await
+        .map_err(|_| "Stream receiver dropped".into())
 }
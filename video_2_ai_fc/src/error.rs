@@ -13,6 +13,12 @@ pub enum Error {
     #[from]
     Json(serde_json::Error),
 
+    #[from]
+    Io(std::io::Error),
+
+    #[from]
+    Http(reqwest::Error),
+
     #[from]
     RpcCall(Box<rpc_router::CallError>),
 }
@@ -24,3 +30,40 @@ impl From<&str> for Error {
         Self::Custom(err.to_string())
     }
 }
+
+impl Error {
+    /// Network hiccups, rate limits and server-side failures, worth retrying.
+    pub fn is_transient(&self) -> bool {
+        use async_openai::error::OpenAIError;
+
+        match self {
+            Error::Http(err) | Error::OpenAi(OpenAIError::Reqwest(err)) => is_transient_http(err),
+            Error::OpenAi(OpenAIError::StreamError(_)) => true,
+            // Only known error objects: an `ApiError` carries no HTTP status, and one
+            // without type nor code may as well be a permanent 4xx. (async-openai
+            // already retries the 5xx responses it reports that way.)
+            Error::OpenAi(OpenAIError::ApiError(api_err)) => {
+                let is_any = |value: &Option<String>, transient: &[&str]| {
+                    value.as_deref().is_some_and(|v| transient.contains(&v))
+                };
+                is_any(&api_err.code, TRANSIENT_API_CODES)
+                    || is_any(&api_err.r#type, TRANSIENT_API_TYPES)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// `code` of the OpenAI error object for a 429 that is not a quota exhaustion.
+const TRANSIENT_API_CODES: &[&str] = &["rate_limit_exceeded"];
+
+/// `type` of the OpenAI error object for server-side failures.
+const TRANSIENT_API_TYPES: &[&str] = &["server_error", "overloaded_error", "timeout"];
+
+fn is_transient_http(err: &reqwest::Error) -> bool {
+    err.is_timeout()
+        || err.is_connect()
+        || err.status().is_some_and(|status| {
+            status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+        })
+}
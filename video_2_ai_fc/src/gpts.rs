@@ -1,5 +1,3 @@
-pub const MODEL: &str = MODEL_3_TURBO;
-
 pub const MODEL_4_TURBO: &str = "gpt-4o-mini";
 
 pub const MODEL_3_TURBO: &str = "gpt-3.5-turbo";
@@ -1,8 +1,12 @@
 mod error;
 pub use error::{Error, Result};
+pub mod batch;
 pub mod chat;
+pub mod config;
+pub mod conv;
 pub mod gpts;
 pub mod oa_client;
+pub mod replay;
+pub mod stream;
 pub mod tools;
-pub mod conv;
-pub mod utils;
\ No newline at end of file
+pub mod utils;
@@ -1,10 +1,179 @@
-use crate::error::Result;
-use std::sync::Arc;
+use crate::{
+    config::OaConfig,
+    error::Result,
+    replay::{Exchange, ExchangeResponse, Fixture, Recorder, Replayer},
+};
+use std::{path::Path, sync::Arc};
 
-use async_openai::{Client, config::OpenAIConfig};
+use async_openai::{
+    Client,
+    config::OpenAIConfig,
+    error::OpenAIError,
+    types::{
+        ChatCompletionResponseStream, CreateChatCompletionRequest, CreateChatCompletionResponse,
+    },
+};
+use futures::{StreamExt, TryStreamExt};
 
-pub type OaClient = Arc<Client<OpenAIConfig>>;
+#[derive(Clone)]
+pub struct OaClient {
+    backend: Backend,
+    config: Arc<OaConfig>,
+}
+
+#[derive(Clone)]
+enum Backend {
+    Live(Arc<Client<OpenAIConfig>>),
+    Recording(Arc<Client<OpenAIConfig>>, Recorder),
+    Replay(Replayer),
+}
+
+impl OaClient {
+    pub async fn create_chat(
+        &self,
+        request: CreateChatCompletionRequest,
+    ) -> Result<CreateChatCompletionResponse> {
+        match &self.backend {
+            Backend::Live(client) => Ok(client.chat().create(request).await?),
+            Backend::Recording(client, recorder) => {
+                let response = client.chat().create(request.clone()).await;
+                record_api_error(recorder, &request, &response);
+                let response = response?;
+                recorder.push(Exchange {
+                    request,
+                    response: ExchangeResponse::Complete(response.clone()),
+                });
+                Ok(response)
+            }
+            Backend::Replay(replayer) => match replayer.next(&request)? {
+                ExchangeResponse::Complete(response) => Ok(response),
+                ExchangeResponse::Stream(_) => {
+                    Err("Replay expected a complete response, fixture has a stream".into())
+                }
+                ExchangeResponse::Error(err) => Err(OpenAIError::from(err).into()),
+            },
+        }
+    }
+
+    /// While recording, the stream is read to the end before being handed back.
+    pub async fn create_chat_stream(
+        &self,
+        request: CreateChatCompletionRequest,
+    ) -> Result<ChatCompletionResponseStream> {
+        let chunks = match &self.backend {
+            Backend::Live(client) => return Ok(client.chat().create_stream(request).await?),
+            Backend::Recording(client, recorder) => {
+                let stream = client.chat().create_stream(request.clone()).await;
+                record_api_error(recorder, &request, &stream);
+                let stream = stream?;
+                let chunks: Vec<_> = stream.try_collect().await?;
+                recorder.push(Exchange {
+                    request,
+                    response: ExchangeResponse::Stream(chunks.clone()),
+                });
+                chunks
+            }
+            Backend::Replay(replayer) => match replayer.next(&request)? {
+                ExchangeResponse::Stream(chunks) => chunks,
+                ExchangeResponse::Complete(_) => {
+                    return Err("Replay expected a stream, fixture has a complete response".into());
+                }
+                ExchangeResponse::Error(err) => return Err(OpenAIError::from(err).into()),
+            },
+        };
+
+        Ok(futures::stream::iter(chunks).map(Ok).boxed())
+    }
+
+    pub fn config(&self) -> &OaConfig {
+        &self.config
+    }
+
+    pub fn default_model(&self) -> &str {
+        &self.config.model
+    }
+
+    pub fn temperature(&self) -> Option<f32> {
+        self.config.temperature
+    }
+
+    pub fn recorder(&self) -> Option<&Recorder> {
+        match &self.backend {
+            Backend::Recording(_, recorder) => Some(recorder),
+            _ => None,
+        }
+    }
+
+    pub fn replayer(&self) -> Option<&Replayer> {
+        match &self.backend {
+            Backend::Replay(replayer) => Some(replayer),
+            _ => None,
+        }
+    }
+}
+
+/// Keeps the error objects the API answers with, so replays fail the same way.
+fn record_api_error<T>(
+    recorder: &Recorder,
+    request: &CreateChatCompletionRequest,
+    response: &std::result::Result<T, OpenAIError>,
+) {
+    if let Err(OpenAIError::ApiError(err)) = response {
+        recorder.push(Exchange {
+            request: request.clone(),
+            response: ExchangeResponse::Error(err.into()),
+        });
+    }
+}
 
+/// Client configured from the `OA_*` env variables (OpenAI defaults otherwise).
 pub fn new_oa_client() -> Result<OaClient> {
-    Ok(Client::new().into())
+    new_oa_client_with(OaConfig::from_env()?)
+}
+
+pub fn new_oa_client_with(config: OaConfig) -> Result<OaClient> {
+    let client = new_client(&config)?;
+
+    Ok(OaClient {
+        backend: Backend::Live(Arc::new(client)),
+        config: Arc::new(config),
+    })
+}
+
+/// Live client that also keeps every exchange, see [`OaClient::recorder`].
+pub fn new_recording_client(config: OaConfig) -> Result<OaClient> {
+    let client = new_client(&config)?;
+
+    Ok(OaClient {
+        backend: Backend::Recording(Arc::new(client), Recorder::default()),
+        config: Arc::new(config),
+    })
+}
+
+/// Offline client answering from a fixture file, see [`Replayer`].
+///
+/// `config` must match the one used while recording (model, temperature).
+pub fn new_replay_client(config: OaConfig, fixture: impl AsRef<Path>) -> Result<OaClient> {
+    let fixture = Fixture::load(fixture)?;
+
+    Ok(OaClient {
+        backend: Backend::Replay(Replayer::new(fixture)),
+        config: Arc::new(config),
+    })
+}
+
+fn new_client(config: &OaConfig) -> Result<Client<OpenAIConfig>> {
+    let oa_config = OpenAIConfig::new()
+        .with_api_base(&config.base_url)
+        .with_api_key(config.api_key());
+
+    let mut http_client = reqwest::Client::builder();
+    if let Some(timeout) = config.timeout() {
+        http_client = http_client.timeout(timeout);
+    }
+    if let Some(connect_timeout) = config.connect_timeout() {
+        http_client = http_client.connect_timeout(connect_timeout);
+    }
+
+    Ok(Client::with_config(oa_config).with_http_client(http_client.build()?))
 }
@@ -0,0 +1,173 @@
+use std::{
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use async_openai::{
+    error::{ApiError, OpenAIError},
+    types::{
+        CreateChatCompletionRequest, CreateChatCompletionResponse,
+        CreateChatCompletionStreamResponse,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Result,
+    conv::{self, ToolCallRecord},
+};
+
+/// One request sent to the chat API and what came back for it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Exchange {
+    pub request: CreateChatCompletionRequest,
+    pub response: ExchangeResponse,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExchangeResponse {
+    Complete(CreateChatCompletionResponse),
+    Stream(Vec<CreateChatCompletionStreamResponse>),
+    /// The error object the API answered with, replayed as an [`OpenAIError::ApiError`].
+    Error(RecordedError),
+}
+
+/// Serializable copy of [`ApiError`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedError {
+    pub message: String,
+    #[serde(default, rename = "type")]
+    pub kind: Option<String>,
+    #[serde(default)]
+    pub param: Option<String>,
+    #[serde(default)]
+    pub code: Option<String>,
+}
+
+impl From<&ApiError> for RecordedError {
+    fn from(err: &ApiError) -> Self {
+        RecordedError {
+            message: err.message.clone(),
+            kind: err.r#type.clone(),
+            param: err.param.clone(),
+            code: err.code.clone(),
+        }
+    }
+}
+
+impl From<RecordedError> for OpenAIError {
+    fn from(err: RecordedError) -> Self {
+        OpenAIError::ApiError(ApiError {
+            message: err.message,
+            r#type: err.kind,
+            param: err.param,
+            code: err.code,
+        })
+    }
+}
+
+/// The JSON fixture file: exchanges in the order they happened.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Fixture {
+    pub exchanges: Vec<Exchange>,
+}
+
+impl Fixture {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// Collects exchanges made through a recording client.
+#[derive(Debug, Clone, Default)]
+pub struct Recorder {
+    exchanges: Arc<Mutex<Vec<Exchange>>>,
+}
+
+impl Recorder {
+    pub(crate) fn push(&self, exchange: Exchange) {
+        self.exchanges.lock().unwrap().push(exchange);
+    }
+
+    pub fn fixture(&self) -> Fixture {
+        Fixture {
+            exchanges: self.exchanges.lock().unwrap().clone(),
+        }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        self.fixture().save(path)
+    }
+}
+
+/// Serves the exchanges of a [`Fixture`] in order.
+///
+/// Every incoming request must equal the recorded one, so a replay fails as soon
+/// as the conversation diverges (different question, tool output, model, ...).
+#[derive(Debug, Clone)]
+pub struct Replayer {
+    fixture: Arc<Fixture>,
+    served: Arc<Mutex<Vec<CreateChatCompletionRequest>>>,
+}
+
+impl Replayer {
+    pub fn new(fixture: Fixture) -> Self {
+        Replayer {
+            fixture: Arc::new(fixture),
+            served: Arc::default(),
+        }
+    }
+
+    pub(crate) fn next(&self, request: &CreateChatCompletionRequest) -> Result<ExchangeResponse> {
+        let mut served = self.served.lock().unwrap();
+        let idx = served.len();
+
+        let exchange = self
+            .fixture
+            .exchanges
+            .get(idx)
+            .ok_or(format!("Replay has no exchange #{idx}"))?;
+
+        if &exchange.request != request {
+            let expected = serde_json::to_string(&exchange.request)?;
+            let actual = serde_json::to_string(request)?;
+            return Err(format!(
+                "Replay request #{idx} does not match fixture\n expected: {expected}\n   actual: {actual}"
+            )
+            .into());
+        }
+
+        served.push(request.clone());
+        Ok(exchange.response.clone())
+    }
+
+    /// Requests served so far.
+    pub fn served(&self) -> Vec<CreateChatCompletionRequest> {
+        self.served.lock().unwrap().clone()
+    }
+
+    pub fn is_exhausted(&self) -> bool {
+        self.served.lock().unwrap().len() == self.fixture.exchanges.len()
+    }
+
+    /// Tool calls found in the last request sent, with the tool results that were returned.
+    pub fn tool_calls(&self) -> Vec<ToolCallRecord> {
+        self.served
+            .lock()
+            .unwrap()
+            .last()
+            .map(|request| conv::tool_calls(&request.messages))
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,101 @@
+use std::collections::BTreeMap;
+
+use async_openai::types::{
+    ChatCompletionMessageToolCall, ChatCompletionMessageToolCallChunk, ChatCompletionToolType,
+    FunctionCall,
+};
+use serde_json::Value;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConvEvent {
+    TextDelta(String),
+    ToolCallStarted {
+        tool_call_id: String,
+        fn_name: String,
+    },
+    ToolResult {
+        tool_call_id: String,
+        fn_name: String,
+        response: Value,
+    },
+    Done {
+        content: String,
+    },
+}
+
+#[derive(Debug, Default)]
+struct PartialToolCall {
+    id: String,
+    fn_name: String,
+    arguments: String,
+    started: bool,
+}
+
+impl PartialToolCall {
+    fn start(&mut self) -> Option<(String, String)> {
+        if self.started || self.id.is_empty() || self.fn_name.is_empty() {
+            return None;
+        }
+        self.started = true;
+        Some((self.id.clone(), self.fn_name.clone()))
+    }
+}
+
+/// Rebuilds complete tool calls from the fragments spread over stream chunks.
+///
+/// Fragments are keyed by their `index`; `id`, function name and `arguments` are
+/// each concatenated across chunks. The name precedes the arguments, so a call counts
+/// as started once its first argument fragment arrives.
+#[derive(Debug, Default)]
+pub struct ToolCallAssembler {
+    calls: BTreeMap<u32, PartialToolCall>,
+}
+
+impl ToolCallAssembler {
+    /// Merges a chunk and returns `(tool_call_id, fn_name)` when the call starts.
+    pub fn push(&mut self, chunk: ChatCompletionMessageToolCallChunk) -> Option<(String, String)> {
+        let call = self.calls.entry(chunk.index).or_default();
+
+        if let Some(id) = chunk.id {
+            call.id.push_str(&id);
+        }
+        let mut has_arguments = false;
+        if let Some(function) = chunk.function {
+            if let Some(name) = function.name {
+                call.fn_name.push_str(&name);
+            }
+            if let Some(arguments) = function.arguments {
+                has_arguments = !arguments.is_empty();
+                call.arguments.push_str(&arguments);
+            }
+        }
+
+        if has_arguments { call.start() } else { None }
+    }
+
+    /// Starts the calls that never received an argument fragment, at the end of the stream.
+    pub fn flush(&mut self) -> Vec<(String, String)> {
+        self.calls
+            .values_mut()
+            .filter_map(PartialToolCall::start)
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.calls.is_empty()
+    }
+
+    pub fn finish(self) -> Vec<ChatCompletionMessageToolCall> {
+        self.calls
+            .into_values()
+            .map(|call| ChatCompletionMessageToolCall {
+                id: call.id,
+                r#type: ChatCompletionToolType::Function,
+                function: FunctionCall {
+                    name: call.fn_name,
+                    arguments: call.arguments,
+                },
+            })
+            .collect()
+    }
+}
@@ -1,12 +1,18 @@
-use std::sync::Arc;
+use std::{collections::HashSet, sync::Arc};
 
-use async_openai::types::ChatCompletionTool;
+use async_openai::types::{ChatCompletionMessageToolCall, ChatCompletionTool};
 use rpc_router::Router;
+use serde_json::Value;
+
+use super::{Approval, ApprovalRequest, AutoDeny, ToolApproval};
+use crate::Result;
 
 #[derive(Clone)]
 pub struct AiTools {
     router: Router,
     chat_tools: Arc<Vec<ChatCompletionTool>>,
+    approval_required: Arc<HashSet<String>>,
+    approval: Arc<dyn ToolApproval>,
 }
 
 impl AiTools {
@@ -14,8 +20,23 @@ impl AiTools {
         AiTools {
             router,
             chat_tools: Arc::new(chat_tools),
+            approval_required: Arc::default(),
+            approval: Arc::new(AutoDeny),
         }
     }
+
+    /// Marks a tool as side-effecting: it only runs once the approval callback agrees.
+    pub fn require_approval(mut self, fn_name: impl Into<String>) -> Self {
+        Arc::make_mut(&mut self.approval_required).insert(fn_name.into());
+        self
+    }
+
+    /// Callback asked before running tools marked with [`AiTools::require_approval`].
+    /// Defaults to [`AutoDeny`].
+    pub fn with_approval(mut self, approval: impl ToolApproval + 'static) -> Self {
+        self.approval = Arc::new(approval);
+        self
+    }
 }
 
 impl AiTools {
@@ -26,4 +47,34 @@ impl AiTools {
     pub fn chat_tools_clone(&self) -> Vec<ChatCompletionTool> {
         self.chat_tools.as_ref().clone()
     }
+
+    pub fn requires_approval(&self, fn_name: &str) -> bool {
+        self.approval_required.contains(fn_name)
+    }
+
+    /// Runs the tool call through the router, after approval when the tool needs it.
+    ///
+    /// A denied call is not an error: the denial becomes the tool result for the model.
+    pub async fn call(&self, tool_call: &ChatCompletionMessageToolCall) -> Result<Value> {
+        let fn_name = tool_call.function.name.clone();
+        let params: Value = serde_json::from_str(&tool_call.function.arguments)?;
+
+        if self.requires_approval(&fn_name) {
+            let request = ApprovalRequest {
+                fn_name: fn_name.clone(),
+                params: params.clone(),
+            };
+            if let Approval::Denied { reason } = self.approval.approve(&request).await {
+                return Ok(Approval::denied_response(&fn_name, &reason));
+            }
+        }
+
+        let call_result = self
+            .router
+            .call_route(None, fn_name, Some(params))
+            .await
+            .map_err(Box::new)?;
+
+        Ok(call_result.value)
+    }
 }
@@ -0,0 +1,113 @@
+use std::{collections::HashSet, io::Write};
+
+use futures::future::BoxFuture;
+use serde_json::{Value, json};
+
+#[derive(Debug, Clone)]
+pub struct ApprovalRequest {
+    pub fn_name: String,
+    pub params: Value,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Approval {
+    Approved,
+    Denied { reason: String },
+}
+
+impl Approval {
+    pub fn denied(reason: impl Into<String>) -> Self {
+        Approval::Denied {
+            reason: reason.into(),
+        }
+    }
+
+    /// Tool result sent back to the model in place of the real call.
+    pub(crate) fn denied_response(fn_name: &str, reason: &str) -> Value {
+        json!({
+            "error": format!("Call to '{fn_name}' was denied by the user"),
+            "reason": reason,
+        })
+    }
+}
+
+/// Decides whether a tool marked with [`AiTools::require_approval`](super::AiTools::require_approval)
+/// may run.
+pub trait ToolApproval: Send + Sync {
+    fn approve<'a>(&'a self, request: &'a ApprovalRequest) -> BoxFuture<'a, Approval>;
+}
+
+impl<F> ToolApproval for F
+where
+    F: Fn(&ApprovalRequest) -> Approval + Send + Sync,
+{
+    fn approve<'a>(&'a self, request: &'a ApprovalRequest) -> BoxFuture<'a, Approval> {
+        let approval = self(request);
+        Box::pin(async move { approval })
+    }
+}
+
+pub struct AutoDeny;
+
+impl ToolApproval for AutoDeny {
+    fn approve<'a>(&'a self, _request: &'a ApprovalRequest) -> BoxFuture<'a, Approval> {
+        Box::pin(async { Approval::denied("Tool calls are not allowed") })
+    }
+}
+
+pub struct AllowList(pub HashSet<String>);
+
+impl AllowList {
+    pub fn new<I, S>(fn_names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        AllowList(fn_names.into_iter().map(Into::into).collect())
+    }
+}
+
+impl ToolApproval for AllowList {
+    fn approve<'a>(&'a self, request: &'a ApprovalRequest) -> BoxFuture<'a, Approval> {
+        Box::pin(async move {
+            if self.0.contains(&request.fn_name) {
+                Approval::Approved
+            } else {
+                Approval::denied(format!("'{}' is not in the allow list", request.fn_name))
+            }
+        })
+    }
+}
+
+/// Asks on stdin, anything but `y`/`yes` denies.
+pub struct CliPrompt;
+
+impl ToolApproval for CliPrompt {
+    fn approve<'a>(&'a self, request: &'a ApprovalRequest) -> BoxFuture<'a, Approval> {
+        let prompt = format!(
+            "Allow tool '{}' with arguments {}? [y/N] ",
+            request.fn_name, request.params
+        );
+
+        Box::pin(async move {
+            let answer = tokio::task::spawn_blocking(move || {
+                let mut stdout = std::io::stdout();
+                stdout.write_all(prompt.as_bytes())?;
+                stdout.flush()?;
+
+                let mut answer = String::new();
+                std::io::stdin().read_line(&mut answer)?;
+                Ok::<_, std::io::Error>(answer)
+            })
+            .await;
+
+            match answer {
+                Ok(Ok(answer)) if matches!(answer.trim().to_lowercase().as_str(), "y" | "yes") => {
+                    Approval::Approved
+                }
+                Ok(Ok(_)) => Approval::denied("Denied at the prompt"),
+                _ => Approval::denied("Could not read the prompt answer"),
+            }
+        })
+    }
+}
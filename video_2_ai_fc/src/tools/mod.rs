@@ -1,8 +1,10 @@
 mod ai_tools;
+mod approval;
 mod currency;
 mod spec;
 
 pub use ai_tools::*;
+pub use approval::*;
 pub use spec::*;
 
 use crate::Result;
@@ -40,14 +40,14 @@ fn into_spec_params(mut json_schema: Value) -> Result<Value> {
         if let Some(Value::String(ref_def)) = prop_value.pointer_mut("/allOf/0/$ref") {
             let ref_def = ref_def.trim_start_matches('#');
 
-            if let Some(Value::Object(refed_obj)) = json_schema.pointer(ref_def) {
-                if let Some(prop_obj) = prop_value.as_object_mut() {
-                    for (sub_name, sub_val) in refed_obj {
-                        prop_obj.insert(sub_name.to_string(), sub_val.clone());
-                    }
-
-                    prop_obj.remove("allOf");
+            if let Some(Value::Object(refed_obj)) = json_schema.pointer(ref_def)
+                && let Some(prop_obj) = prop_value.as_object_mut()
+            {
+                for (sub_name, sub_val) in refed_obj {
+                    prop_obj.insert(sub_name.to_string(), sub_val.clone());
                 }
+
+                prop_obj.remove("allOf");
             }
         }
     }
@@ -0,0 +1,141 @@
+use std::{path::PathBuf, time::Duration};
+
+use serde_json::json;
+use video_2_ai_fc::{
+    batch::{self, BatchConfig, BatchItem},
+    config::OaConfig,
+    oa_client::new_replay_client,
+    tools::new_ai_tools,
+};
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name)
+}
+
+#[test]
+fn load_prompts() {
+    let prompts = batch::load_prompts_jsonl(fixture("prompts.jsonl")).unwrap();
+
+    assert_eq!(
+        prompts,
+        vec![
+            "Convert 3 Dollars to Euro",
+            "why is the sky red (be concise)"
+        ]
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn batch_report() {
+    let oa_client =
+        new_replay_client(OaConfig::default(), fixture("batch_two_questions.json")).unwrap();
+    let model = oa_client.default_model().to_string();
+    let mut questions = batch::load_prompts_jsonl(fixture("prompts.jsonl")).unwrap();
+    questions.push("A question missing from the fixture".to_string());
+
+    // Replay serves exchanges in order, so run one question at a time.
+    let config = BatchConfig {
+        max_concurrency: 1,
+        requests_per_minute: Some(1200),
+        ..Default::default()
+    };
+    let items = batch::run_batch(
+        oa_client,
+        new_ai_tools().unwrap(),
+        &model,
+        questions,
+        config,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(items.len(), 3);
+
+    assert_eq!(
+        items[0].answer.as_deref(),
+        Some("3 US Dollars is 2.73 Euro.")
+    );
+    assert_eq!(items[0].tool_calls.len(), 1);
+    assert_eq!(
+        items[0].tool_calls[0].arguments,
+        json!({"amount": 3, "from": "USD", "to": "EUR"})
+    );
+    assert_eq!(items[0].usage.prompt_tokens, 71 + 113);
+    assert_eq!(items[0].usage.total_tokens, 95 + 124);
+
+    assert!(items[1].answer.is_some());
+    assert!(items[1].tool_calls.is_empty());
+    assert_eq!(items[1].usage.total_tokens, 91);
+
+    // Not transient, so not retried.
+    assert!(items[2].answer.is_none());
+    assert!(items[2].error.is_some());
+
+    // One attempt per chat request: the tool call takes two.
+    let attempts: Vec<u32> = items.iter().map(|item| item.attempts).collect();
+    assert_eq!(attempts, [2, 1, 1]);
+
+    // 1200 rpm spaces chat requests 50ms apart (on the paused clock), so every
+    // item waits exactly one slot: the second request of the first, and the
+    // first request of the others.
+    let latencies: Vec<u64> = items.iter().map(|item| item.latency_ms).collect();
+    assert_eq!(latencies, [50, 50, 50]);
+
+    let report_path = std::env::temp_dir().join("video_2_ai_fc_batch_report.jsonl");
+    batch::write_report_jsonl(&report_path, &items).unwrap();
+    let report = std::fs::read_to_string(&report_path).unwrap();
+    let lines: Vec<BatchItem> = report
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    std::fs::remove_file(&report_path).unwrap();
+
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[0].question, "Convert 3 Dollars to Euro");
+    assert_eq!(lines[0].tool_calls, items[0].tool_calls);
+}
+
+#[tokio::test(start_paused = true)]
+async fn batch_retries_transient_errors_only() {
+    let oa_client = new_replay_client(OaConfig::default(), fixture("batch_retry.json")).unwrap();
+    let model = oa_client.default_model().to_string();
+    let questions = vec![
+        "why is the sky red (be concise)".to_string(),
+        "Convert 3 Dollars to Euro".to_string(),
+    ];
+
+    let config = BatchConfig {
+        max_concurrency: 1,
+        max_retries: 2,
+        retry_backoff: Duration::from_millis(100),
+        ..Default::default()
+    };
+    let items = batch::run_batch(
+        oa_client.clone(),
+        new_ai_tools().unwrap(),
+        &model,
+        questions,
+        config,
+    )
+    .await
+    .unwrap();
+
+    // Rate limited once, then answered after one backoff.
+    assert!(items[0].answer.is_some());
+    assert_eq!(items[0].attempts, 2);
+    assert_eq!(items[0].latency_ms, 100);
+
+    // An invalid API key is permanent: a retry would have run past the fixture.
+    assert!(
+        items[1]
+            .error
+            .as_deref()
+            .unwrap()
+            .contains("invalid_api_key")
+    );
+    assert_eq!(items[1].attempts, 1);
+    assert_eq!(items[1].latency_ms, 0);
+    assert!(oa_client.replayer().unwrap().is_exhausted());
+}
@@ -0,0 +1,212 @@
+use std::{
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use futures::StreamExt;
+use serde_json::json;
+use video_2_ai_fc::{
+    Result,
+    config::OaConfig,
+    conv::{self, ToolCallRecord},
+    oa_client::{OaClient, new_replay_client},
+    stream::ConvEvent,
+    tools::{AiTools, AllowList, Approval, ApprovalRequest, AutoDeny, new_ai_tools},
+};
+
+fn replay_client(fixture: &str) -> OaClient {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(fixture);
+    new_replay_client(OaConfig::default(), path).unwrap()
+}
+
+async fn ask(oa_client: &OaClient, question: &str) -> Result<String> {
+    ask_with(oa_client, new_ai_tools()?, question).await
+}
+
+async fn ask_with(oa_client: &OaClient, ai_tools: AiTools, question: &str) -> Result<String> {
+    let model = oa_client.default_model().to_string();
+    conv::send_user_msg(oa_client.clone(), ai_tools, &model, question).await
+}
+
+#[tokio::test]
+async fn replay_tool_call() {
+    let oa_client = replay_client("convert_usd_eur.json");
+
+    let answer = ask(&oa_client, "Convert 3 Dollars to Euro").await.unwrap();
+
+    let replayer = oa_client.replayer().unwrap();
+    assert_eq!(answer, "3 US Dollars is 2.73 Euro.");
+    assert!(replayer.is_exhausted());
+    assert_eq!(
+        replayer.tool_calls(),
+        vec![ToolCallRecord {
+            fn_name: "get_currency_rate".to_string(),
+            arguments: json!({"amount": 3, "from": "USD", "to": "EUR"}),
+            response: Some(json!({"converted": 2.73, "to": "EUR"})),
+        }]
+    );
+}
+
+#[tokio::test]
+async fn replay_several_tool_rounds() {
+    let oa_client = replay_client("convert_two_rounds.json");
+
+    let answer = ask(&oa_client, "Convert 3 Dollars to Euro, then to Hryvnia")
+        .await
+        .unwrap();
+
+    let replayer = oa_client.replayer().unwrap();
+    assert_eq!(
+        answer,
+        "3 US Dollars is 2.73 Euro, which is 114.66 Hryvnia."
+    );
+    assert!(replayer.is_exhausted());
+    let fn_args: Vec<_> = replayer
+        .tool_calls()
+        .into_iter()
+        .map(|call| call.arguments)
+        .collect();
+    assert_eq!(
+        fn_args,
+        [
+            json!({"amount": 3, "from": "USD", "to": "EUR"}),
+            json!({"amount": 2.73, "from": "EUR", "to": "UAH"}),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn replay_no_tool() {
+    let oa_client = replay_client("sky_no_tool.json");
+
+    let answer = ask(&oa_client, "why is the sky red (be concise)")
+        .await
+        .unwrap();
+
+    let replayer = oa_client.replayer().unwrap();
+    assert!(answer.starts_with("The sky looks red"));
+    assert!(replayer.is_exhausted());
+    assert!(replayer.tool_calls().is_empty());
+}
+
+#[tokio::test]
+async fn replay_diverging_question_fails() {
+    let oa_client = replay_client("convert_usd_eur.json");
+
+    let res = ask(&oa_client, "Convert 4 Dollars to Euro").await;
+
+    assert!(res.is_err());
+    assert!(oa_client.replayer().unwrap().served().is_empty());
+}
+
+#[tokio::test]
+async fn replay_stream_tool_call() {
+    let oa_client = replay_client("convert_eur_uah_stream.json");
+    let model = oa_client.default_model().to_string();
+
+    let events: Vec<ConvEvent> = conv::send_user_msg_stream(
+        oa_client.clone(),
+        new_ai_tools().unwrap(),
+        &model,
+        "Convert 5 Euro to Hryvnia",
+    )
+    .map(|event| event.unwrap())
+    .collect()
+    .await;
+
+    assert_eq!(
+        events,
+        vec![
+            ConvEvent::ToolCallStarted {
+                tool_call_id: "call_fx2".to_string(),
+                fn_name: "get_currency_rate".to_string(),
+            },
+            ConvEvent::ToolResult {
+                tool_call_id: "call_fx2".to_string(),
+                fn_name: "get_currency_rate".to_string(),
+                response: json!({"converted": 210.0, "to": "UAH"}),
+            },
+            ConvEvent::TextDelta("5 Euro".to_string()),
+            ConvEvent::TextDelta(" is 210".to_string()),
+            ConvEvent::TextDelta(" Hryvnia.".to_string()),
+            ConvEvent::Done {
+                content: "5 Euro is 210 Hryvnia.".to_string(),
+            },
+        ]
+    );
+
+    let replayer = oa_client.replayer().unwrap();
+    assert!(replayer.is_exhausted());
+    assert_eq!(
+        replayer.tool_calls()[0].arguments,
+        json!({"amount": 5, "from": "EUR", "to": "UAH"})
+    );
+}
+
+#[tokio::test]
+async fn approval_denied_is_reported_to_model() {
+    let oa_client = replay_client("convert_denied.json");
+    let ai_tools = new_ai_tools()
+        .unwrap()
+        .require_approval("get_currency_rate")
+        .with_approval(AutoDeny);
+
+    let answer = ask_with(&oa_client, ai_tools, "Convert 3 Dollars to Euro")
+        .await
+        .unwrap();
+
+    let replayer = oa_client.replayer().unwrap();
+    assert!(answer.contains("denied"));
+    assert!(replayer.is_exhausted());
+    assert_eq!(
+        replayer.tool_calls()[0].response,
+        Some(json!({
+            "error": "Call to 'get_currency_rate' was denied by the user",
+            "reason": "Tool calls are not allowed",
+        }))
+    );
+}
+
+#[tokio::test]
+async fn approval_callback_sees_parsed_arguments() {
+    let oa_client = replay_client("convert_usd_eur.json");
+    let seen: Arc<Mutex<Vec<ApprovalRequest>>> = Arc::default();
+    let seen_in_cb = seen.clone();
+    let ai_tools = new_ai_tools()
+        .unwrap()
+        .require_approval("get_currency_rate")
+        .with_approval(move |request: &ApprovalRequest| {
+            seen_in_cb.lock().unwrap().push(request.clone());
+            Approval::Approved
+        });
+
+    let answer = ask_with(&oa_client, ai_tools, "Convert 3 Dollars to Euro")
+        .await
+        .unwrap();
+
+    let seen = seen.lock().unwrap();
+    assert_eq!(answer, "3 US Dollars is 2.73 Euro.");
+    assert_eq!(seen.len(), 1);
+    assert_eq!(seen[0].fn_name, "get_currency_rate");
+    assert_eq!(
+        seen[0].params,
+        json!({"amount": 3, "from": "USD", "to": "EUR"})
+    );
+}
+
+#[tokio::test]
+async fn approval_allow_list() {
+    let oa_client = replay_client("convert_usd_eur.json");
+    let ai_tools = new_ai_tools()
+        .unwrap()
+        .require_approval("get_currency_rate")
+        .with_approval(AllowList::new(["get_currency_rate"]));
+
+    let answer = ask_with(&oa_client, ai_tools, "Convert 3 Dollars to Euro")
+        .await
+        .unwrap();
+
+    assert_eq!(answer, "3 US Dollars is 2.73 Euro.");
+}
@@ -0,0 +1,79 @@
+use async_openai::types::{ChatCompletionMessageToolCallChunk, FunctionCallStream};
+use video_2_ai_fc::stream::ToolCallAssembler;
+
+fn chunk(
+    index: u32,
+    id: Option<&str>,
+    name: Option<&str>,
+    arguments: Option<&str>,
+) -> ChatCompletionMessageToolCallChunk {
+    ChatCompletionMessageToolCallChunk {
+        index,
+        id: id.map(String::from),
+        r#type: None,
+        function: Some(FunctionCallStream {
+            name: name.map(String::from),
+            arguments: arguments.map(String::from),
+        }),
+    }
+}
+
+#[test]
+fn reassembles_split_fragments() {
+    let mut assembler = ToolCallAssembler::default();
+
+    assert_eq!(
+        assembler.push(chunk(0, Some("call_"), Some("get_cur"), None)),
+        None
+    );
+    assert_eq!(
+        assembler.push(chunk(0, Some("1"), Some("rency_rate"), None)),
+        None
+    );
+    assert_eq!(
+        assembler.push(chunk(0, None, None, Some("{\"from\":"))),
+        Some(("call_1".to_string(), "get_currency_rate".to_string()))
+    );
+    assert_eq!(assembler.push(chunk(0, None, None, Some("\"USD\"}"))), None);
+    assert!(assembler.flush().is_empty());
+
+    let calls = assembler.finish();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].id, "call_1");
+    assert_eq!(calls[0].function.name, "get_currency_rate");
+    assert_eq!(calls[0].function.arguments, "{\"from\":\"USD\"}");
+}
+
+#[test]
+fn interleaved_calls_keep_their_index() {
+    let mut assembler = ToolCallAssembler::default();
+
+    assembler.push(chunk(1, Some("call_b"), Some("b"), None));
+    assembler.push(chunk(0, Some("call_a"), Some("a"), None));
+    assert_eq!(
+        assembler.push(chunk(1, None, None, Some("{\"x\":1}"))),
+        Some(("call_b".to_string(), "b".to_string()))
+    );
+    assembler.push(chunk(0, None, None, Some("{}")));
+
+    let calls = assembler.finish();
+    let ids: Vec<&str> = calls.iter().map(|c| c.id.as_str()).collect();
+    assert_eq!(ids, ["call_a", "call_b"]);
+    assert_eq!(calls[1].function.arguments, "{\"x\":1}");
+}
+
+#[test]
+fn flush_starts_calls_without_arguments() {
+    let mut assembler = ToolCallAssembler::default();
+
+    assert_eq!(
+        assembler.push(chunk(0, Some("call_1"), Some("ping"), Some(""))),
+        None
+    );
+
+    assert_eq!(
+        assembler.flush(),
+        [("call_1".to_string(), "ping".to_string())]
+    );
+    assert!(assembler.flush().is_empty());
+}